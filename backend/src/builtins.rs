@@ -0,0 +1,932 @@
+use crate::runner::{Runner, Unwinding, unwrap_provider};
+
+use crate::value::Value;
+use crate::value::none::NoneValue;
+use crate::value::number::NumberValue;
+use crate::value::string::StringValue;
+use crate::value::closure::ClosureValue;
+use crate::value::scope::{ScopeValue, ScopeData};
+use crate::value::range::RangeValue;
+use crate::value::error::*;
+use crate::value::list::ListValue;
+use crate::value::map::MapValue;
+use crate::value::status::StatusValue;
+
+use crate::{cast, cast_mut};
+
+use helpers::{elvis, result_or, some_or};
+
+use num_traits::ToPrimitive;
+
+use processing::jobs::{JobState, restore_signal};
+use processing::signals::{SIGINT, find_signal, send_signal, catch_signal, get_signal_name};
+
+use std::collections::HashMap;
+use std::rc::Rc;
+
+/// The value of the command, or the
+/// error it fails with.
+pub type BuiltinResult = Result<Box<dyn Value>, Box<ErrorValue>>;
+
+/// A command implemented in Rust. It receives
+/// the evaluated arguments, the command name
+/// first, and the runner, which holds the
+/// current `scope` and the `redirections`.
+/// The returned error is raised the same
+/// way as the ones of `throw`.
+pub trait Builtin {
+    fn call(&self, runner: &mut Runner, arguments: Vec<Box<dyn Value>>) -> BuiltinResult;
+}
+
+impl <F> Builtin for F where F: Fn(&mut Runner, Vec<Box<dyn Value>>) -> BuiltinResult {
+    fn call(&self, runner: &mut Runner, arguments: Vec<Box<dyn Value>>) -> BuiltinResult {
+        self(runner, arguments)
+    }
+}
+
+pub type Builtins = HashMap<String, Rc<dyn Builtin>>;
+
+/// The builtins every runner starts with.
+pub fn create_builtins() -> Builtins {
+    let mut builtins = Builtins::new();
+
+    let mut add = |name: &str, builtin: Rc<dyn Builtin>| {
+        builtins.insert(name.to_owned(), builtin);
+    };
+
+    add("builtin", Rc::new(run_builtin));
+    add("exit", Rc::new(run_exit));
+    add("pass", Rc::new(run_pass));
+    add("if", Rc::new(run_if));
+    add("while", Rc::new(run_while));
+    add("for", Rc::new(run_for));
+    add("range", Rc::new(run_range));
+    add("return", Rc::new(run_return));
+    add("break", Rc::new(run_break));
+    add("continue", Rc::new(run_continue));
+    add("try", Rc::new(run_try));
+    add("throw", Rc::new(run_throw));
+    add("export", Rc::new(run_export));
+    add("unset", Rc::new(run_unset));
+    add("env", Rc::new(run_env));
+    add("with", Rc::new(run_with));
+    add("cd", Rc::new(run_cd));
+    add("pwd", Rc::new(run_pwd));
+    add("pushd", Rc::new(run_pushd));
+    add("popd", Rc::new(run_popd));
+    add("dirs", Rc::new(run_dirs));
+    add("jobs", Rc::new(run_jobs));
+    add("fg", Rc::new(run_fg));
+    add("bg", Rc::new(run_bg));
+    add("wait", Rc::new(run_wait));
+    add("kill", Rc::new(run_kill));
+    add("trap", Rc::new(run_trap));
+
+    builtins
+}
+
+fn usage(message: &str) -> BuiltinResult {
+    Err(ErrorValue::create(USAGE_ERROR, message))
+}
+
+/// `builtin name args...` runs the builtin
+/// even if a closure shadows it.
+fn run_builtin(runner: &mut Runner, mut command: Vec<Box<dyn Value>>) -> BuiltinResult {
+    if command.len() < 2 {
+        return usage("Malformed builtin > Expected a name");
+    }
+
+    command.remove(0);
+    let name = command[0].to_string();
+
+    let builtin = some_or! { runner.builtins.get(&name).cloned() => {
+        return Err(ErrorValue::create(NAME_ERROR, &format!("No such builtin > {:?}", &name)));
+    }};
+
+    builtin.call(runner, command)
+}
+
+fn run_exit(runner: &mut Runner, command: Vec<Box<dyn Value>>) -> BuiltinResult {
+    if command.len() >= 2 {
+        let code = command[1].to_string();
+
+        runner.exit_code = result_or! { code.parse::<i32>() => {
+            return usage(&format!("Malformed exit > The code is not a number > {:?}", &code));
+        }};
+    }
+
+    runner.should_exit = true;
+    Ok(NoneValue::create())
+}
+
+fn run_pass(_runner: &mut Runner, mut command: Vec<Box<dyn Value>>) -> BuiltinResult {
+    if command.len() >= 2 {
+        Ok(unwrap_provider(command.remove(1)))
+    } else {
+        Ok(NoneValue::create())
+    }
+}
+
+fn run_return(runner: &mut Runner, mut command: Vec<Box<dyn Value>>) -> BuiltinResult {
+    let value = if command.len() >= 2 {
+        unwrap_provider(command.remove(1))
+    } else {
+        NoneValue::create()
+    };
+
+    runner.unwinding = Some(Unwinding::Return(value));
+    Ok(NoneValue::create())
+}
+
+fn run_break(runner: &mut Runner, _command: Vec<Box<dyn Value>>) -> BuiltinResult {
+    runner.unwinding = Some(Unwinding::Break);
+    Ok(NoneValue::create())
+}
+
+fn run_continue(runner: &mut Runner, _command: Vec<Box<dyn Value>>) -> BuiltinResult {
+    runner.unwinding = Some(Unwinding::Continue);
+    Ok(NoneValue::create())
+}
+
+/// `range end`, `range start end` or
+/// `range start end step`.
+fn run_range(_runner: &mut Runner, command: Vec<Box<dyn Value>>) -> BuiltinResult {
+    let mut numbers = vec![];
+
+    for it in command.into_iter().skip(1) {
+        let it = unwrap_provider(it);
+
+        if let Some(number) = cast!(it => NumberValue) {
+            numbers.push(some_or! { number.to_i32() => {
+                return usage(&format!("Malformed range > The number is too large > {}", number.value));
+            }});
+        } else {
+            return usage(&format!("Malformed range > Not a number > {:?}", it.to_string()));
+        }
+    }
+
+    match numbers[..] {
+        [end] => Ok(RangeValue::create(0, end, 1)),
+        [start, end] => Ok(RangeValue::create(start, end, 1)),
+        [_, _, 0] => usage("Malformed range > The step is zero"),
+        [start, end, step] => Ok(RangeValue::create(start, end, step)),
+        _ => usage("Malformed range > Expected 1 to 3 numbers"),
+    }
+}
+
+/// Returns the items a `for` loop goes
+/// through. Strings are split into lines,
+/// maps give their keys. Lists are copied,
+/// so the body may change them.
+fn iterate(value: &dyn Value) -> Option<Box<dyn Iterator<Item = Box<dyn Value>>>> {
+    if let Some(range) = cast!(value => RangeValue) {
+        let numbers = range.iter()
+            .map(|it| NumberValue::create(it) as Box<dyn Value>);
+        return Some(Box::new(numbers));
+    }
+
+    if let Some(list) = cast!(value => ListValue) {
+        return Some(Box::new(list.items().into_iter()));
+    }
+
+    if let Some(map) = cast!(value => MapValue) {
+        let keys = map.keys().into_iter()
+            .map(|it| StringValue::create(&it) as Box<dyn Value>);
+        return Some(Box::new(keys));
+    }
+
+    if let Some(string) = cast!(value => StringValue) {
+        let lines = string.value.lines()
+            .map(|it| StringValue::create(it) as Box<dyn Value>)
+            .collect::<Vec<Box<dyn Value>>>();
+        return Some(Box::new(lines.into_iter()));
+    }
+
+    return None;
+}
+
+/// The value the `catch` closure receives.
+fn error_to_scope(error: &ErrorValue) -> Box<ScopeValue> {
+    let mut scope = ScopeValue::create(ScopeData::create(None));
+
+    let line: Box<dyn Value> = match error.line {
+        Some(it) => NumberValue::create(it as i32),
+        None => NoneValue::create(),
+    };
+
+    scope.set_value("kind", StringValue::create(&error.kind));
+    scope.set_value("message", StringValue::create(&error.message));
+    scope.set_value("code", NumberValue::create(error.code));
+    scope.set_value("line", line);
+    scope.set_value("trace", StringValue::create(&error.trace.join("\n")));
+    scope
+}
+
+/// Restores the error caught by `catch`,
+/// so that `throw $error` rethrows it.
+fn scope_to_error(scope: &mut ScopeValue) -> Option<Box<ErrorValue>> {
+    let kind = scope.get_value("kind")?.to_string();
+    let message = scope.get_value("message")?.to_string();
+    let mut error = ErrorValue::create(&kind, &message);
+
+    if let Some(code) = scope.get_value("code") {
+        if let Some(it) = cast!(code => NumberValue).and_then(|it| it.to_i32()) {
+            error.code = it;
+        }
+    }
+
+    if let Some(line) = scope.get_value("line") {
+        if let Some(number) = cast!(line => NumberValue) {
+            error.line = number.value.to_usize();
+        }
+    }
+
+    if let Some(trace) = scope.get_value("trace") {
+        error.trace = trace.to_string()
+            .lines()
+            .map(|it| it.to_owned())
+            .collect();
+    }
+
+    return Some(error);
+}
+
+fn to_closure(value: Box<dyn Value>) -> Option<Box<ClosureValue>> {
+    let value = unwrap_provider(value);
+    cast!(value => ClosureValue).map(|it| ClosureValue::create(it.data.clone()))
+}
+
+/// Handles `break` and `continue` after
+/// a loop body. Returns false if the loop
+/// must stop.
+fn handle_loop_unwinding(runner: &mut Runner) -> bool {
+    // even a loop without commands
+    // can be interrupted
+    runner.check_signals();
+
+    match runner.unwinding {
+        Some(Unwinding::Break) => {
+            runner.unwinding = None;
+            false
+        }
+        Some(Unwinding::Continue) => {
+            runner.unwinding = None;
+            !runner.should_exit
+        }
+        Some(Unwinding::Return(..)) => false,
+        Some(Unwinding::Error(..)) => false,
+        None => !runner.should_exit,
+    }
+}
+
+/// `while { cond } { ... }`
+fn run_while(runner: &mut Runner, command: Vec<Box<dyn Value>>) -> BuiltinResult {
+    if command.len() != 3 {
+        return usage("Malformed while > Expected a condition and a body");
+    }
+
+    let mut rest = command.into_iter().skip(1);
+    let mut condition = unwrap_provider(rest.next().unwrap());
+    let body = unwrap_provider(rest.next().unwrap());
+
+    let closure = some_or! { cast!(body => ClosureValue) => {
+        return usage(&format!("Malformed while > The body is not a closure > {:?}", body.to_string()));
+    }};
+
+    let mut result: Box<dyn Value> = NoneValue::create();
+
+    loop {
+        let is_true = runner.check_condition(condition.duplicate_or_move());
+
+        if !is_true || runner.is_unwinding() {
+            break;
+        }
+
+        result = runner.enter_closure(closure, vec![], vec![]);
+
+        if !handle_loop_unwinding(runner) {
+            break;
+        }
+    }
+
+    return Ok(result);
+}
+
+/// `for item in $items { ... }`
+fn run_for(runner: &mut Runner, command: Vec<Box<dyn Value>>) -> BuiltinResult {
+    if command.len() != 5 || command[2].to_string() != "in" {
+        return usage("Malformed for > Expected `for name in values { ... }`");
+    }
+
+    let mut rest = command.into_iter().skip(1);
+    let name = rest.next().unwrap().to_string();
+    let values = unwrap_provider(rest.nth(1).unwrap());
+    let body = unwrap_provider(rest.next().unwrap());
+
+    let closure = some_or! { cast!(body => ClosureValue) => {
+        return usage(&format!("Malformed for > The body is not a closure > {:?}", body.to_string()));
+    }};
+
+    let items = some_or! { iterate(values.as_ref()) => {
+        return usage(&format!("Malformed for > Can't iterate over a {}", values.get_type_name()));
+    }};
+
+    let mut result: Box<dyn Value> = NoneValue::create();
+
+    for item in items {
+        result = runner.enter_closure(closure, vec![], vec![(name.clone(), item)]);
+
+        if !handle_loop_unwinding(runner) {
+            break;
+        }
+    }
+
+    return Ok(result);
+}
+
+/// `if cond { ... } else if cond { ... } else { ... }`
+fn run_if(runner: &mut Runner, command: Vec<Box<dyn Value>>) -> BuiltinResult {
+    let mut rest = command.into_iter().skip(1);
+
+    loop {
+        let condition = some_or! { rest.next() => {
+            return usage("Malformed if > Expected a condition");
+        }};
+
+        let body = some_or! { rest.next() => {
+            return usage("Malformed if > Expected a body");
+        }};
+
+        let body = unwrap_provider(body);
+
+        let closure = some_or! { cast!(body => ClosureValue) => {
+            return usage(&format!("Malformed if > The body is not a closure > {:?}", body.to_string()));
+        }};
+
+        if runner.check_condition(condition) {
+            return Ok(runner.enter_closure(closure, vec![], vec![]));
+        }
+
+        let word = some_or! { rest.next() => return Ok(NoneValue::create()) };
+
+        if word.to_string() != "else" {
+            return usage(&format!("Malformed if > Expected else > {:?}", word.to_string()));
+        }
+
+        let next = some_or! { rest.next() => {
+            return usage("Malformed if > Expected a body after else");
+        }};
+
+        if let Some(..) = cast!(next => StringValue) {
+            if next.to_string() == "if" {
+                continue;
+            }
+        }
+
+        let next = unwrap_provider(next);
+
+        let closure = some_or! { cast!(next => ClosureValue) => {
+            return usage(&format!("Malformed if > The body is not a closure > {:?}", next.to_string()));
+        }};
+
+        if let Some(extra) = rest.next() {
+            return usage(&format!("Malformed if > Unexpected value after else > {:?}", extra.to_string()));
+        }
+
+        return Ok(runner.enter_closure(closure, vec![], vec![]));
+    }
+}
+
+/// `try { ... } catch { error -> ... } finally { ... }`
+fn run_try(runner: &mut Runner, command: Vec<Box<dyn Value>>) -> BuiltinResult {
+    let mut rest = command.into_iter().skip(1);
+
+    let body = some_or! { rest.next().and_then(to_closure) => {
+        return usage("Malformed try > Expected a closure");
+    }};
+
+    let mut handler = None;
+    let mut finalizer = None;
+
+    while let Some(word) = rest.next() {
+        let word = word.to_string();
+
+        let closure = some_or! { rest.next().and_then(to_closure) => {
+            return usage(&format!("Malformed try > Expected a closure after {}", &word));
+        }};
+
+        match &*word {
+            "catch" if handler.is_none() && finalizer.is_none() => handler = Some(closure),
+            "finally" if finalizer.is_none() => finalizer = Some(closure),
+            _ => return usage(&format!("Malformed try > Unexpected {:?}", &word)),
+        }
+    }
+
+    if handler.is_none() && finalizer.is_none() {
+        return usage("Malformed try > Expected catch or finally");
+    }
+
+    let mut result = runner.enter_closure(&body, vec![], vec![]);
+
+    if let Some(handler) = &handler {
+        match runner.unwinding.take() {
+            Some(Unwinding::Error(error)) => {
+                result = runner.enter_closure(handler, vec![error_to_scope(&error)], vec![]);
+            }
+            other => runner.unwinding = other,
+        }
+    }
+
+    // `finally` runs even if the body has
+    // returned or failed, and then the
+    // unwinding goes on, unless `finally`
+    // has started a new one
+    if let Some(finalizer) = &finalizer {
+        if !runner.should_exit {
+            let pending = runner.unwinding.take();
+            runner.enter_closure(finalizer, vec![], vec![]);
+
+            if runner.unwinding.is_none() {
+                runner.unwinding = pending;
+            }
+        }
+    }
+
+    return Ok(result);
+}
+
+/// `throw message...` or `throw $error`
+/// for the errors caught by `catch`.
+fn run_throw(_runner: &mut Runner, mut command: Vec<Box<dyn Value>>) -> BuiltinResult {
+    if command.len() == 2 {
+        let mut value = unwrap_provider(command.remove(1));
+
+        if let Some(scope) = cast_mut!(value => ScopeValue) {
+            if let Some(error) = scope_to_error(scope) {
+                return Err(error);
+            }
+        }
+
+        command.push(value);
+    }
+
+    let message = command.iter()
+        .skip(1)
+        .map(|it| it.to_string())
+        .collect::<Vec<String>>()
+        .join(" ");
+
+    Err(ErrorValue::create(USER_ERROR, &message))
+}
+
+/// `export NAME` or `export NAME=value`
+/// makes the global variable a part of
+/// the environment of external commands.
+fn run_export(runner: &mut Runner, command: Vec<Box<dyn Value>>) -> BuiltinResult {
+    if command.len() < 2 {
+        return usage("Malformed export > Expected variable names");
+    }
+
+    for it in command.iter().skip(1) {
+        let word = it.to_string();
+
+        let (name, value) = match word.split_once('=') {
+            Some((name, value)) => (name.to_owned(), StringValue::create(value) as Box<dyn Value>),
+            None => {
+                let value = some_or! { runner.scope.resolve(&word) => {
+                    return Err(ErrorValue::create(NAME_ERROR, &format!("Can't export an undefined variable > {:?}", &word)));
+                }};
+                (word, value)
+            }
+        };
+
+        if name.is_empty() {
+            return usage(&format!("Malformed export > Expected a name > {:?}", it.to_string()));
+        }
+
+        let mut global = runner.global_scope.data.borrow_mut();
+        global.set_value(&name, value);
+        global.exported.insert(name);
+    }
+
+    Ok(NoneValue::create())
+}
+
+/// `unset NAME...` removes the global variables,
+/// so they are no longer exported as well.
+fn run_unset(runner: &mut Runner, command: Vec<Box<dyn Value>>) -> BuiltinResult {
+    for it in command.iter().skip(1) {
+        runner.global_scope.data.borrow_mut().remove_value(&it.to_string());
+    }
+
+    Ok(NoneValue::create())
+}
+
+/// The environment as a map.
+fn run_env(runner: &mut Runner, _command: Vec<Box<dyn Value>>) -> BuiltinResult {
+    let entries = runner.get_environment().into_iter()
+        .map(|(key, value)| (key, StringValue::create(&value) as Box<dyn Value>))
+        .collect();
+
+    Ok(MapValue::create(entries))
+}
+
+/// `with [NAME: value, ...] { ... }` runs the
+/// closure with the variables added to the
+/// environment of its external commands.
+fn run_with(runner: &mut Runner, command: Vec<Box<dyn Value>>) -> BuiltinResult {
+    if command.len() != 3 {
+        return usage("Malformed with > Expected a map and a closure");
+    }
+
+    let mut rest = command.into_iter().skip(1);
+    let overrides = unwrap_provider(rest.next().unwrap());
+
+    let overrides = some_or! { cast!(overrides => MapValue) => {
+        return usage("Malformed with > Expected a map");
+    }};
+
+    let body = some_or! { rest.next().and_then(to_closure) => {
+        return usage("Malformed with > Expected a closure");
+    }};
+
+    let depth = runner.environment_overrides.len();
+
+    for (key, value) in overrides.items() {
+        runner.environment_overrides.push((key, value.to_string()));
+    }
+
+    let result = runner.enter_closure(&body, vec![], vec![]);
+    runner.environment_overrides.truncate(depth);
+
+    return Ok(result);
+}
+
+/// The working directory of the shell,
+/// or an empty string if it's gone.
+fn current_directory() -> String {
+    match std::env::current_dir() {
+        Ok(it) => it.to_string_lossy().into_owned(),
+        Err(_) => String::new(),
+    }
+}
+
+/// Makes the directory the working one of the
+/// shell and updates `PWD` and `OLDPWD`.
+fn change_directory(runner: &mut Runner, path: &str) -> Result<(), Box<ErrorValue>> {
+    let old = current_directory();
+
+    if let Err(error) = std::env::set_current_dir(path) {
+        return Err(ErrorValue::create(IO_ERROR, &format!("Couldn't change the directory > {:?} > {}", path, error)));
+    }
+
+    let mut global = runner.global_scope.data.borrow_mut();
+    global.set_value("OLDPWD", StringValue::create(&old));
+    global.set_value("PWD", StringValue::create(&current_directory()));
+    global.exported.insert("OLDPWD".to_owned());
+    global.exported.insert("PWD".to_owned());
+
+    Ok(())
+}
+
+/// Relative paths that don't start with
+/// `.` are looked up in the directories
+/// listed in `CDPATH` first.
+fn find_directory(runner: &mut Runner, path: &str) -> String {
+    if path.starts_with('/') || path.starts_with('.') {
+        return path.to_owned();
+    }
+
+    let roots = some_or! { runner.scope.resolve("CDPATH") => return path.to_owned() };
+
+    for root in roots.to_string().split(':').filter(|it| !it.is_empty()) {
+        let candidate = std::path::Path::new(root).join(path);
+
+        if candidate.is_dir() {
+            return candidate.to_string_lossy().into_owned();
+        }
+    }
+
+    path.to_owned()
+}
+
+/// `cd` goes to `HOME`, `cd -` goes back
+/// to `OLDPWD`, and `cd path` goes to
+/// the path.
+fn run_cd(runner: &mut Runner, command: Vec<Box<dyn Value>>) -> BuiltinResult {
+    let target = match command.len() {
+        1 => "~".to_owned(),
+        2 => command[1].to_string(),
+        _ => return usage("Malformed cd > Expected a single directory"),
+    };
+
+    let path = match &*target {
+        "~" => runner.scope.resolve("HOME").map(|it| it.to_string()),
+        "-" => runner.scope.resolve("OLDPWD").map(|it| it.to_string()),
+        _ => Some(find_directory(runner, &target)),
+    };
+
+    let path = some_or! { path => {
+        return Err(ErrorValue::create(NAME_ERROR, &format!("Malformed cd > Don't know where {:?} is", &target)));
+    }};
+
+    change_directory(runner, &path)?;
+    Ok(NoneValue::create())
+}
+
+fn run_pwd(_runner: &mut Runner, _command: Vec<Box<dyn Value>>) -> BuiltinResult {
+    Ok(StringValue::create(&current_directory()))
+}
+
+/// `pushd path` remembers the current directory
+/// and goes to the path, `pushd` alone swaps
+/// the current directory with the remembered one.
+fn run_pushd(runner: &mut Runner, command: Vec<Box<dyn Value>>) -> BuiltinResult {
+    let old = current_directory();
+
+    let path = match command.len() {
+        1 => some_or! { runner.directory_stack.pop() => {
+            return usage("Malformed pushd > No other directory");
+        }},
+        2 => find_directory(runner, &command[1].to_string()),
+        _ => return usage("Malformed pushd > Expected a single directory"),
+    };
+
+    if let Err(error) = change_directory(runner, &path) {
+        if command.len() == 1 {
+            runner.directory_stack.push(path);
+        }
+
+        return Err(error);
+    }
+
+    runner.directory_stack.push(old);
+    Ok(NoneValue::create())
+}
+
+/// `popd` goes back to the directory
+/// remembered by `pushd`.
+fn run_popd(runner: &mut Runner, command: Vec<Box<dyn Value>>) -> BuiltinResult {
+    if command.len() != 1 {
+        return usage("Malformed popd > Expected no arguments");
+    }
+
+    let path = some_or! { runner.directory_stack.pop() => {
+        return usage("Malformed popd > The directory stack is empty");
+    }};
+
+    if let Err(error) = change_directory(runner, &path) {
+        runner.directory_stack.push(path);
+        return Err(error);
+    }
+
+    Ok(NoneValue::create())
+}
+
+/// The current directory followed by the
+/// remembered ones, the latest first.
+fn run_dirs(runner: &mut Runner, _command: Vec<Box<dyn Value>>) -> BuiltinResult {
+    let mut directories = vec![StringValue::create(&current_directory()) as Box<dyn Value>];
+
+    for it in runner.directory_stack.iter().rev() {
+        directories.push(StringValue::create(it));
+    }
+
+    Ok(ListValue::create(directories))
+}
+
+fn job_error(message: &str) -> Box<ErrorValue> {
+    ErrorValue::create(JOB_ERROR, message)
+}
+
+/// `%2` or `2` is the job with the id,
+/// nothing means the latest one. Returns
+/// its index in `jobs`.
+fn find_job(runner: &Runner, reference: Option<String>) -> Result<usize, Box<ErrorValue>> {
+    let reference = some_or! { reference => {
+        return match runner.jobs.len() {
+            0 => Err(job_error("There are no jobs")),
+            it => Ok(it - 1),
+        };
+    }};
+
+    let id = reference.strip_prefix('%').unwrap_or(&reference).parse::<usize>().ok();
+
+    id.and_then(|id| runner.jobs.iter().position(|it| it.id == id))
+        .ok_or_else(|| job_error(&format!("No such job > {}", reference)))
+}
+
+/// The jobs as maps with the `id`, the
+/// `state`, the `command` and the `group`.
+/// The finished ones are only shown once.
+fn run_jobs(runner: &mut Runner, _command: Vec<Box<dyn Value>>) -> BuiltinResult {
+    let mut result = vec![];
+
+    for it in &mut runner.jobs {
+        it.update();
+
+        result.push(MapValue::create(vec![
+            ("id".to_owned(), NumberValue::create(it.id as i32) as Box<dyn Value>),
+            ("state".to_owned(), StringValue::create(&it.state.describe())),
+            ("command".to_owned(), StringValue::create(&it.command)),
+            ("group".to_owned(), NumberValue::create(it.group)),
+        ]) as Box<dyn Value>);
+    }
+
+    runner.jobs.retain(|it| !matches!(it.state, JobState::Done(..)));
+    Ok(ListValue::create(result))
+}
+
+/// `fg [%id]` continues the job
+/// and waits for it to finish.
+fn run_fg(runner: &mut Runner, command: Vec<Box<dyn Value>>) -> BuiltinResult {
+    if command.len() > 2 {
+        return usage("Malformed fg > Expected a single job");
+    }
+
+    let index = find_job(runner, command.get(1).map(|it| it.to_string()))?;
+    let mut job = runner.jobs.remove(index);
+
+    if let Err(error) = job.resume() {
+        runner.jobs.insert(index, job);
+        return Err(job_error(&format!("Couldn't continue the job > {}", error)));
+    }
+
+    if runner.job_control {
+        runner.print_error(&job.command);
+    }
+
+    let status = runner.wait_in_foreground(job);
+    runner.set_status(status.clone());
+    Ok(status)
+}
+
+/// `bg [%id]` continues the stopped
+/// job in the background.
+fn run_bg(runner: &mut Runner, command: Vec<Box<dyn Value>>) -> BuiltinResult {
+    if command.len() > 2 {
+        return usage("Malformed bg > Expected a single job");
+    }
+
+    let index = find_job(runner, command.get(1).map(|it| it.to_string()))?;
+
+    if let Err(error) = runner.jobs[index].resume() {
+        return Err(job_error(&format!("Couldn't continue the job > {}", error)));
+    }
+
+    Ok(NoneValue::create())
+}
+
+/// `wait [%id...]` waits for the jobs, all
+/// but the stopped ones by default. The
+/// result is the status of the last one.
+fn run_wait(runner: &mut Runner, command: Vec<Box<dyn Value>>) -> BuiltinResult {
+    let mut ids = vec![];
+
+    for it in &mut runner.jobs {
+        it.update();
+    }
+
+    if command.len() == 1 {
+        ids.extend(runner.jobs.iter().filter(|it| !matches!(it.state, JobState::Stopped(..))).map(|it| it.id));
+    }
+
+    for it in command.iter().skip(1) {
+        ids.push(runner.jobs[find_job(runner, Some(it.to_string()))?].id);
+    }
+
+    let mut status = StatusValue::create(0, None);
+
+    for id in ids {
+        let index = some_or! { runner.jobs.iter().position(|it| it.id == id) => continue };
+        let job = &mut runner.jobs[index];
+
+        if job.state == JobState::Running {
+            job.wait();
+        }
+
+        match job.state {
+            JobState::Done(it) => {
+                status = StatusValue::from_exit_status(&it);
+                runner.jobs.remove(index);
+            }
+            JobState::Stopped(signal) => status = StatusValue::create(128 + signal, Some(signal)),
+            JobState::Running => {}
+        }
+    }
+
+    runner.set_status(status.clone());
+    Ok(status)
+}
+
+/// `kill [-SIGNAL] target...` sends the signal,
+/// `TERM` by default, to the jobs given as
+/// `%id` and to the processes given as pids.
+fn run_kill(runner: &mut Runner, command: Vec<Box<dyn Value>>) -> BuiltinResult {
+    let mut targets = command.iter().skip(1).map(|it| it.to_string()).peekable();
+    let mut name = "TERM".to_owned();
+
+    if let Some(it) = targets.peek().and_then(|it| it.strip_prefix('-')).map(|it| it.to_owned()) {
+        name = it;
+        targets.next();
+    }
+
+    let signal = some_or! { find_signal(&name) => {
+        return usage(&format!("Malformed kill > Unknown signal {}", name));
+    }};
+
+    let targets = targets.collect::<Vec<String>>();
+
+    if targets.is_empty() {
+        return usage("Malformed kill > Expected a job or a pid");
+    }
+
+    for it in targets {
+        let result = if it.starts_with('%') {
+            let index = find_job(runner, Some(it.clone()))?;
+            runner.jobs[index].signal(signal)
+        } else if let Ok(pid) = it.parse::<i32>() {
+            send_signal(pid, signal)
+        } else {
+            return usage(&format!("Malformed kill > Expected a job or a pid, got {}", it));
+        };
+
+        if let Err(error) = result {
+            return Err(job_error(&format!("Couldn't signal {} > {}", it, error)));
+        }
+    }
+
+    Ok(NoneValue::create())
+}
+
+/// `EXIT` and `0` mean the exit of the shell.
+fn find_trap_signal(name: &str) -> Option<i32> {
+    match name {
+        "EXIT" | "0" => Some(0),
+        _ => find_signal(name),
+    }
+}
+
+/// `trap NAME... { ... }` calls the closure when
+/// one of the signals arrives or, for `EXIT`, when
+/// the shell exits. `trap NAME...` removes the
+/// traps, and `trap` alone gives them as a map.
+fn run_trap(runner: &mut Runner, mut command: Vec<Box<dyn Value>>) -> BuiltinResult {
+    if command.len() == 1 {
+        let mut signals = runner.traps.keys().cloned().collect::<Vec<i32>>();
+        signals.sort();
+
+        let entries = signals.into_iter()
+            .map(|it| {
+                let name = if it == 0 { "EXIT".to_owned() } else { get_signal_name(it) };
+                (name, ClosureValue::create(runner.traps[&it].data.clone()) as Box<dyn Value>)
+            })
+            .collect();
+
+        return Ok(MapValue::create(entries));
+    }
+
+    let last = unwrap_provider(command.pop().unwrap());
+    let handler = cast!(last => ClosureValue).map(|it| ClosureValue::create(it.data.clone()));
+
+    if handler.is_none() {
+        command.push(last);
+    }
+
+    if command.len() == 1 {
+        return usage("Malformed trap > Expected a signal");
+    }
+
+    let mut signals = vec![];
+
+    for it in command.iter().skip(1) {
+        let name = it.to_string();
+
+        signals.push(some_or! { find_trap_signal(&name) => {
+            return usage(&format!("Malformed trap > Unknown signal {}", name));
+        }});
+    }
+
+    for signal in signals {
+        if let Some(closure) = &handler {
+            if signal != 0 {
+                if let Err(error) = catch_signal(signal) {
+                    return usage(&format!("Malformed trap > Can't trap {} > {}", get_signal_name(signal), error));
+                }
+            }
+
+            runner.traps.insert(signal, ClosureValue::create(closure.data.clone()));
+            continue;
+        }
+
+        runner.traps.remove(&signal);
+
+        // in the interactive mode Ctrl-C
+        // keeps interrupting the statements
+        if signal != 0 && (signal != SIGINT || !runner.interactive) {
+            let _ = restore_signal(signal);
+        }
+    }
+
+    Ok(NoneValue::create())
+}
@@ -0,0 +1,154 @@
+use crate::runner::Runner;
+
+use crate::value::Value;
+use crate::value::closure::ClosureValue;
+use crate::value::error::*;
+
+use crate::cast;
+
+use parsing::stream::*;
+use parsing::stream::wrapper_stream::{WrapperStream};
+use parsing::stream::accumulator_stream::{SimpleAccumulatorStream};
+
+use frontend::lexer::{Lexer};
+use frontend::parser::{Parser};
+
+use processing::Channel;
+
+use std::io::Cursor;
+
+pub type EvalResult = Result<Box<dyn Value>, Box<ErrorValue>>;
+
+/// Runs Cash code on behalf of a
+/// host application. The state is kept
+/// between the calls, so a script can
+/// define closures the host calls later.
+pub struct Interpreter {
+    pub runner: Runner,
+}
+
+/// Turns the `#!/usr/bin/env cash` line into
+/// an empty one, so that the line numbers stay
+/// the same.
+pub fn skip_shebang(code: String) -> String {
+    if code.starts_with("#!") {
+        return match code.find('\n') {
+            Some(index) => code[index..].to_owned(),
+            None => String::new(),
+        };
+    }
+
+    return code;
+}
+
+impl Interpreter {
+    pub fn new() -> Interpreter {
+        Interpreter {
+            runner: Runner::new(),
+        }
+    }
+
+    /// Runs the statements one by one like a
+    /// script does. Stops at the first error
+    /// nobody has handled and returns it, or
+    /// at `exit`. Otherwise the result is the
+    /// value of the last statement.
+    pub fn eval_str(&mut self, code: &str) -> EvalResult {
+        let mut input_stream = WrapperStream::new(Cursor::new(code.to_owned()));
+        let mut accumulator_stream = SimpleAccumulatorStream::new(&mut input_stream);
+        let mut tokenizer = Lexer::new(&mut accumulator_stream);
+        let mut parser = Parser::new(&mut tokenizer);
+
+        while parser.has_next() && !self.runner.should_exit {
+            let wrapped = parser.grab();
+
+            if !parser.has_next() {
+                break;
+            }
+
+            let mut ast = wrapped.borrow_mut();
+            self.runner.line = parser.get_offset();
+
+            match parser.syntax_error.take() {
+                Some(message) => self.runner.fail_syntax(&message),
+                None => self.runner.execute(&mut ast),
+            }
+
+            if let Some(error) = self.runner.error.take() {
+                return Err(error);
+            }
+        }
+
+        Ok(self.runner.value.duplicate_or_move())
+    }
+
+    pub fn eval_file(&mut self, path: &str) -> EvalResult {
+        let code = match std::fs::read_to_string(path) {
+            Ok(it) => it,
+            Err(error) => {
+                let message = format!("Couldn't read the file > {:?} > {}", path, error);
+                return Err(ErrorValue::create(IO_ERROR, &message));
+            }
+        };
+
+        self.eval_str(&skip_shebang(code))
+    }
+
+    /// Calls a closure defined in the global
+    /// scope as if it's been run as a command.
+    pub fn call_closure(&mut self, name: &str, arguments: Vec<Box<dyn Value>>) -> EvalResult {
+        let value = match self.runner.global_scope.resolve(name) {
+            Some(it) => it,
+            None => return Err(ErrorValue::create(NAME_ERROR, &format!("No closure named {}", name))),
+        };
+
+        let closure = match cast!(value => ClosureValue) {
+            Some(it) => it,
+            None => return Err(ErrorValue::create(TYPE_ERROR, &format!("{} is not a closure", name))),
+        };
+
+        self.runner.error = None;
+        self.runner.call_stack.clear();
+        self.runner.call_stack.push((name.to_owned(), self.runner.line));
+
+        let result = self.runner.call_closure(closure, arguments);
+
+        self.runner.call_stack.pop();
+        self.runner.finish_unwinding();
+
+        match self.runner.error.take() {
+            Some(error) => Err(error),
+            None => Ok(result),
+        }
+    }
+
+    pub fn get_variable(&mut self, name: &str) -> Option<Box<dyn Value>> {
+        self.runner.global_scope.get_value(name)
+    }
+
+    pub fn set_variable(&mut self, name: &str, value: Box<dyn Value>) {
+        self.runner.global_scope.set_value(name, value);
+    }
+
+    /// Sets `$0`, `$1`, ... and `$argumentsCount`.
+    pub fn set_arguments(&mut self, arguments: &[String]) {
+        self.runner.set_arguments(arguments);
+    }
+
+    /// Where external commands read from.
+    pub fn set_stdin(&mut self, channel: Channel) {
+        self.runner.redirections.input = channel;
+    }
+
+    /// Where external commands write to.
+    pub fn set_stdout(&mut self, channel: Channel) {
+        self.runner.redirections.output = channel;
+    }
+
+    /// Where external commands write their
+    /// errors to, along with the warnings and
+    /// the job messages of the shell itself.
+    pub fn set_stderr(&mut self, channel: Channel) {
+        self.runner.redirections.error = channel;
+    }
+}
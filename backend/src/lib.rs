@@ -1,2 +1,467 @@
 pub mod value;
 pub mod runner;
+pub mod builtins;
+pub mod interpreter;
+
+#[cfg(test)]
+mod tests {
+    use crate::runner::Runner;
+    use crate::builtins::BuiltinResult;
+    use crate::interpreter::Interpreter;
+    use crate::value::Value;
+    use crate::value::string::StringValue;
+    use crate::value::number::NumberValue;
+    use crate::value::error::{ErrorValue, IO_ERROR};
+
+    use parsing::stream::*;
+    use parsing::stream::wrapper_stream::{WrapperStream};
+    use parsing::stream::accumulator_stream::{SimpleAccumulatorStream};
+
+    use frontend::lexer::{Lexer};
+    use frontend::parser::{Parser};
+
+    use std::sync::Mutex;
+    use std::rc::Rc;
+    use std::io::Read;
+
+    use processing::Channel;
+
+    /// Tests that depend on the working
+    /// directory mustn't run in parallel.
+    static DIRECTORY: Mutex<()> = Mutex::new(());
+
+    fn run(runner: &mut Runner, input: &str) -> Box<dyn Value> {
+        let mut input_stream = WrapperStream::new(
+            input.as_bytes()
+        );
+        let mut accumulator_stream = SimpleAccumulatorStream::new(&mut input_stream);
+        let mut tokenizer = Lexer::new(&mut accumulator_stream);
+        let mut parser = Parser::new(&mut tokenizer);
+
+        while parser.has_next() && !runner.should_exit {
+            let wrapped = parser.grab();
+
+            if !parser.has_next() {
+                break;
+            }
+
+            let mut ast = wrapped.borrow_mut();
+            runner.line = parser.get_offset();
+            runner.run(&mut ast);
+        }
+
+        runner.value.duplicate_or_move()
+    }
+
+    fn assert_result(input: &str, expected: &str) {
+        let mut runner = Runner::new();
+        assert_eq!(expected, run(&mut runner, input).to_string());
+    }
+
+    #[test]
+    fn test_runner_arithmetics() {
+        assert_result("pass (2 + 3 * 4)", "14");
+    }
+
+    #[test]
+    fn test_runner_numbers() {
+        assert_result("pass (2 ^ 100)", "1267650600228229401496703205376");
+        assert_result("pass (0x1F + 0b101 + 0o17 + 1fh)", "82");
+        assert_result("pass (1_000 - -1)", "1001");
+        assert_result("pass (5 -1)", "4");
+        assert_result("pass (4 +3)", "7");
+        assert_result("pass (echo (2 -1))", "1");
+        assert_result("try { pass (5 (echo 1)) } catch { e -> pass $e.kind }", "UsageError");
+        assert_result("pass (99999999999 * 99999999999)", "9999999999800000000001");
+        assert_result("pass (6 / 3)", "2");
+        assert_result("pass (5 / 2)", "2.5");
+        assert_result("pass (-7 % 3)", "-1");
+        assert_result("pass (1.5 + 1)", "2.5");
+        assert_result("pass (2e3)", "2000.0");
+        assert_result("pass (2 ^ -1)", "0.5");
+        assert_result("pass (1 == 1.0)", "True");
+        assert_result("pass (2.5 > 2)", "True");
+        assert_result("try { pass (1.5 / 0) } catch { e -> pass $e.kind }", "ArithmeticError");
+        assert_result("try { pass (0 ^ -1) } catch { e -> pass $e.kind }", "ArithmeticError");
+        assert_result("try { pass (0.0 ^ -1) } catch { e -> pass $e.kind }", "ArithmeticError");
+        assert_result("try { pass (0 ^ -0.5) } catch { e -> pass $e.kind }", "ArithmeticError");
+        assert_result("pass (0 ^ 0)", "1");
+        assert_result("try { pass (10 ^ 1000000000) } catch { e -> pass $e.kind }", "ArithmeticError");
+        assert_result("pass (echo 2021_01_01 1e3 1.50 0x1F 007)", "2021_01_01 1e3 1.50 0x1F 007");
+        assert_result("pass (echo 2021_01_01.txt)", "2021_01_01.txt");
+        assert_result("f = { n -> pass ($n + 1) }\npass (f 0x1F)", "32");
+        assert_result("each = { n -> pass ($n * 2) }\npass (each 0ffh)", "510");
+    }
+
+    #[test]
+    fn test_runner_interpolation() {
+        assert_result("name = (echo Nick)\npass \"Hi, $name!\"", "Hi, Nick!");
+        assert_result("name = (echo Nick)\npass \"${name}s (name)\"", "Nicks Nick");
+        assert_result("pass \"${2 + 3} $(echo 1) $\"", "5 $1 $");
+        assert_result("try { throw boom } catch { e -> pass \"$e.kind: $e.message\" }", "Error: boom");
+        assert_result("name = (echo Nick)\npass '$name'", "$name");
+        assert_result("name = (echo Nick)\npass \"\\$name\\t\\u{41}\"", "$name\tA");
+        assert_result("pass \"cost: $5\"", "cost: $5");
+        assert_result("name = (echo report)\npass \"$name.txt\"", "report.txt");
+        assert_result("try { throw boom } catch { e -> pass \"$e.kind.txt\" }", "Error.txt");
+    }
+
+    #[test]
+    fn test_runner_script_arguments() {
+        let mut runner = Runner::new();
+        runner.set_arguments(&["script.cash".to_owned(), "first".to_owned()]);
+
+        assert_eq!("first", run(&mut runner, "$1").to_string());
+        assert_eq!("1", run(&mut runner, "$argumentsCount").to_string());
+
+        run(&mut runner, "exit 7");
+        assert!(runner.should_exit);
+        assert_eq!(7, runner.exit_code);
+
+        let mut runner = Runner::new();
+        assert_eq!("UsageError", run(&mut runner, "try { exit abc } catch { e -> pass $e.kind }").to_string());
+        assert!(!runner.should_exit);
+    }
+
+    #[test]
+    fn test_runner_status() {
+        assert_result("false\n$?", "1");
+        assert_result("true\nnot $?", "False");
+        assert_result("sh -c 'exit 3'\n$?", "3");
+    }
+
+    #[test]
+    fn test_runner_substitution() {
+        assert_result("pass (echo Hello)", "Hello");
+        assert_result("pass \"[(echo a | tr a b)]\"", "[b]");
+        assert_result("x = (echo 5)\npass $x", "5");
+        assert_result("pass ((2 + 3) * 4)", "20");
+        assert_result("pass (for i in (range 3) { echo $i })", "0\n1\n2");
+        assert_result("f = { echo a; echo b }\npass (f)", "a\nb");
+        assert_result("f = { x = (echo a); return $x }\ng = { echo b; f }\npass (g)", "b");
+        assert_result("f = { x = (echo a); return $x }\npass (f)", "a");
+    }
+
+    #[test]
+    fn test_runner_redirections() {
+        let path = std::env::temp_dir().join("cashRedirections.txt");
+        let path = path.to_string_lossy();
+
+        assert_result(&format!("echo one > @{}\necho two >> @{}\npass (cat < @{})", path, path, path), "one\ntwo");
+        assert_result("pass (sh -c 'echo oops >&2' !> @1)", "oops");
+        assert_result("pass (echo hidden > @null)", "");
+        assert_result("pass (echo hidden > @2 !> @null)", "");
+        assert_result("x = @null\npass $x", "@null");
+        assert_result(&format!("echo three > @{}\npass (cat < @{} | tr e E)", path, path), "thrEE");
+        assert_result(&format!("pass (echo four > @{} | cat)\npass (cat < @{})", path, path), "four");
+        assert_result(&format!("try {{ echo hi > @{} extra }} catch {{ e -> pass $e.kind }}", path), "UsageError");
+
+        std::fs::remove_file(&*path).unwrap();
+    }
+
+    #[test]
+    fn test_runner_if() {
+        assert_result("if 1 { pass a } else { pass b }", "a");
+        assert_result("if 0 { pass a } else { pass b }", "b");
+        assert_result("if 0 { pass a }", "None");
+        assert_result("if { false } { pass a } else if { true } { pass b } else { pass c }", "b");
+        assert_result("if 0 { pass a } else if 0 { pass b } else { pass c }", "c");
+        assert_result("false\nif $? { pass a } else { pass b }", "b");
+        assert_result("x = 1\nif 1 { outerScope.x = 2; y = 3 }\npass $x", "2");
+        assert_result("x = 1\nif 1 { x = 2 }\npass $x", "1");
+        assert_result("x = 1\nf = { x = 2; pass $x }\npass (f)", "2");
+        assert_result("x = 1\nf = { x = 2 }\nf\npass $x", "1");
+        assert_result("if 1 { outerScope.fresh = 1 }\npass $fresh", "1");
+        assert_result("x = 1\nf = { x = 2; if 1 { outerScope.x = 3 }; pass $x }\npass (f)\npass $x", "1");
+        assert_result("x = 1\nf = { x = 2; if 1 { outerScope.x = 3 }; pass $x }\npass (f)", "3");
+        assert_result("f = { n -> if $n { pass (f ($n - 1)) } else { pass done } }\npass (f 3)", "done");
+    }
+
+    #[test]
+    fn test_runner_loops() {
+        assert_result("s = 0\nfor i in (range 5) { outerScope.s = $s + $i }\npass $s", "10");
+        assert_result("s = 0\nfor i in (range 1 10 3) { outerScope.s = $s + $i }\npass $s", "12");
+        assert_result("s = 0\nfor it in (printf 'a\\nb') { outerScope.s = $s + 1 }\npass $s", "2");
+        assert_result("n = 3\nwhile { pass $n } { outerScope.n = $n - 1 }\npass $n", "0");
+        assert_result("s = 0\nfor i in (range 10) { if ($i - 4) { outerScope.s = $s + $i } else { break } }\npass $s", "6");
+        assert_result("s = 0\nfor i in (range 4) { if ($i - 2) { pass 0 } else { continue }; outerScope.s = $s + $i }\npass $s", "4");
+        assert_result("s = 0\nwhile 1 { outerScope.s = $s + 1; if ($s - 3) { pass 0 } else { break } }\npass $s", "3");
+        assert_result("break\npass 1", "1");
+    }
+
+    #[test]
+    fn test_runner_return() {
+        assert_result("f = { return 1; pass 2 }\npass (f)", "1");
+        assert_result("f = { return }\npass (f)", "None");
+        assert_result("f = { n -> if $n { return a }; pass b }\npass (f 1)", "a");
+        assert_result("f = { n -> if $n { return a }; pass b }\npass (f 0)", "b");
+        assert_result("f = { for i in (range 5) { if ($i - 3) { pass 0 } else { return $i } } }\npass (f)", "3");
+        assert_result("f = { g = { return 1 }; g; pass 2 }\npass (f)", "2");
+        assert_result("return 1\npass 2", "2");
+    }
+
+    #[test]
+    fn test_runner_operators() {
+        assert_result("pass (1 < 2)", "True");
+        assert_result("pass (2 <= 1)", "False");
+        assert_result("pass (3 > 2)", "True");
+        assert_result("pass (3 >= 3)", "True");
+        assert_result("pass (3 == 3)", "True");
+        assert_result("pass (3 != 3)", "False");
+        assert_result("pass (7 % 3)", "1");
+        assert_result("pass (3 in (range 5))", "True");
+        assert_result("x = (echo abc)\ny = (echo abd)\npass ($x < $y)", "True");
+        assert_result("x = (echo b)\npass ($x in (echo abc))", "True");
+        assert_result("user = (echo al)\npass ($user in [root, alice])", "False");
+        assert_result("pass ((echo 10) > 9)", "True");
+        assert_result("pass (1 == (echo 1.0))", "True");
+        assert_result("pass ((echo 1.0) == 1)", "True");
+        assert_result("pass ((echo abc) != 1)", "True");
+        assert_result("try { pass (1 < (echo abc)) } catch { e -> pass $e.kind }", "TypeError");
+        assert_result("try { pass (1 <= (echo abc)) } catch { e -> pass $e.kind }", "TypeError");
+        assert_result("try { pass ((echo abc) >= 1) } catch { e -> pass $e.kind }", "TypeError");
+        assert_result("try { pass ([1] > [0]) } catch { e -> pass $e.message }", "Unsupported operand types > List > List");
+        assert_result("pass (1 and 0)", "False");
+        assert_result("pass (0 or 1)", "True");
+        assert_result("pass (1 xor 1)", "False");
+        assert_result("pass (1 nand 0)", "True");
+        assert_result("pass (0 xnor 0)", "True");
+        assert_result("pass (not (1 == 1))", "False");
+        assert_result("x = 1\npass (0 and (x = 2))\npass $x", "1");
+        assert_result("x = 1\npass (1 or (x = 2))\npass $x", "1");
+
+        let path = std::env::temp_dir().join("cashWordOperators.txt");
+        let path = path.to_string_lossy();
+
+        assert_result(&format!("echo rock and roll > @{}\necho a in b >> @{}\npass (cat < @{})", path, path, path), "rock and roll\na in b");
+        assert_result("f = { echo x or y }\npass (f)", "x or y");
+
+        std::fs::remove_file(&*path).unwrap();
+    }
+
+    #[test]
+    fn test_runner_errors() {
+        let mut runner = Runner::new();
+        run(&mut runner, "x = 1\ny = (echo a)\nf = { pass ($x - $y) }\nf");
+
+        let error = runner.error.as_ref().unwrap();
+        assert_eq!("TypeError", error.kind);
+        assert_eq!("Unsupported operand types > Number - String", error.message);
+        assert_eq!(Some(4), error.line);
+        assert_eq!(vec!["f called at line 4".to_owned()], error.trace);
+        assert_eq!(1, runner.exit_code);
+
+        let mut runner = Runner::new();
+        run(&mut runner, "f = {\n    pass 1\n\n    pass (1 / 0)\n}\nf");
+
+        let error = runner.error.as_ref().unwrap();
+        assert_eq!("ArithmeticError", error.kind);
+        assert_eq!(Some(4), error.line);
+        assert_eq!(vec!["f called at line 6".to_owned()], error.trace);
+
+        let mut runner = Runner::new();
+        run(&mut runner, "f = {\n    pass (1 / 0)\n}\nif 1 {\n    pass 1; f\n}");
+
+        let error = runner.error.as_ref().unwrap();
+        assert_eq!(Some(2), error.line);
+        assert_eq!(vec!["f called at line 5".to_owned()], error.trace);
+
+        assert_result("x = 1\nx = ($x / 0)\npass $x", "1");
+        assert_result("pass $nope\npass $?", "1");
+        assert_result("nonexistentCommand\npass $?", "127");
+        assert_result("x = 1\nx = (2 + $nope)\npass $x", "1");
+    }
+
+    #[test]
+    fn test_runner_try() {
+        assert_result("try { pass (1 / 0) } catch { e -> pass $e.kind }", "ArithmeticError");
+        assert_result("try { pass 5 } catch { e -> pass 6 }", "5");
+        assert_result("try { throw no luck } catch { e -> pass $e.message }", "no luck");
+        assert_result("try { nonexistentCommand } catch { e -> pass $e.code }", "127");
+        assert_result("x = 1\ntry { throw a } finally { outerScope.x = 2 }\npass $x", "2");
+        assert_result("x = 0\nf = { try { return 1 } finally { outerScope.x = 3 }; pass 2 }\npass (f)", "1");
+        assert_result("x = 0\nf = { try { return 1 } finally { outerScope.x = 3 }; pass 2 }\nf\npass $x", "3");
+        assert_result("f = { try { throw a } catch { e -> throw $e } }\ntry { f } catch { e -> pass $e.line }", "2");
+        assert_result("try { try { throw a } finally { pass 1 } } catch { e -> pass $e.message }", "a");
+    }
+
+    #[test]
+    fn test_runner_lists() {
+        assert_result("pass [1, 2, (echo a)]", "[1, 2, a]");
+        assert_result("l = [1, 2, 3]\npass $l[0] $l[-1]", "1");
+        assert_result("l = [1, 2, 3]\npass $l[-1]", "3");
+        assert_result("l = [[1, 2], [3]]\ni = 1\npass $l[$i][0]", "3");
+        assert_result("pass ([1] + [2, 3])", "[1, 2, 3]");
+        assert_result("pass (2 in [1, 2])", "True");
+        assert_result("pass ([1, 2] == [1, 2])", "True");
+        assert_result("x = 0\nfor i in [1, 2, 3] { outerScope.x = ($x + $i) }\npass $x", "6");
+        assert_result("pass (not [])", "True");
+        assert_result("l = [1]\npass $l[1]\npass $?", "1");
+        assert_result("l = [1]\n$l[0] = $l\npass (1 in $l)", "False");
+        assert_result("l = [1]\n$l[0] = $l\npass ($l == $l)", "True");
+        assert_result("l = [1]\n$l[0] = $l\npass (echo $l)", "[[...]]");
+    }
+
+    #[test]
+    fn test_runner_maps() {
+        assert_result("pass [a: 1, b: (echo x)]", "[a: 1, b: x]");
+        assert_result("m = [name: (echo Bob)]\npass $m[name]", "Bob");
+        assert_result("m = [a: 1]\n$m[b] = 2\n$m[a] = 3\npass $m", "[a: 3, b: 2]");
+        assert_result("m = [a: 1]\nn = $m\n$n[a] = 2\npass $m[a]", "2");
+        assert_result("m = [a: 1]\nk = (echo a)\npass ($k in $m)", "True");
+        assert_result("keys = (echo)\nfor k in [a: 1, b: 2] { outerScope.keys = ($keys + $k) }\npass $keys", "ab");
+        assert_result("pass ([a: 1] + [b: 2, a: 3])", "[a: 3, b: 2]");
+        assert_result("pass [:]", "[:]");
+        assert_result("l = [1, 2]\n$l[-1] = 5\npass $l", "[1, 5]");
+        assert_result("m = [a: 1]\npass $m[b]\npass $?", "1");
+        assert_result("m = [a: 1]\n$m[a] = $m\npass $m", "[a: [...]]");
+        assert_result("m = [a: 1]\n$m[a] = $m\npass ($m == $m)", "True");
+    }
+
+    #[test]
+    fn test_runner_globs() {
+        let _lock = DIRECTORY.lock().unwrap();
+
+        assert_result("pass (echo Cargo.tom? src/l*.rs build.r[s])", "Cargo.toml src/lib.rs build.rs");
+        assert_result("pass (echo ./Cargo.tom? ././src/l*.rs)", "./Cargo.toml ././src/lib.rs");
+        assert_result("pass (echo src/{lib,value}.rs)", "src/lib.rs src/value.rs");
+        assert_result("pass (echo '*.rs' \"*.rs\" \\*.rs)", "*.rs *.rs *.rs");
+        assert_result("pass (echo *.nope)", "*.nope");
+        assert_result("unmatchedGlobs = (echo empty)\npass (echo *.nope done)", "done");
+        assert_result("unmatchedGlobs = (echo error)\ntry { echo *.nope } catch { e -> pass $e.kind }", "GlobError");
+        assert_result("x = 3\npass (echo ($x*2) (2 * 3))", "6 6");
+    }
+
+    #[test]
+    fn test_runner_environment() {
+        assert_result("HOME = (echo /home/cash)\npass (echo ~ ~/src \\~ '~')", "/home/cash /home/cash/src ~ ~");
+        assert_result("HOME = (echo /home/cash)\npass (sh -c 'echo $HOME')", "/home/cash");
+        assert_result("pass ((echo ~root/x) == (sh -c 'echo ~root/x'))", "True");
+        assert_result("pass (echo ~noSuchCashUser/x)", "~noSuchCashUser/x");
+        assert_result("local = (echo 1)\npass (sh -c 'echo x${local}x')", "xx");
+        assert_result("export cashName=one\npass (sh -c 'echo $cashName')", "one");
+        assert_result("local = (echo 1)\nexport local\npass ((env)[local])", "1");
+        assert_result("export cashName=one\nunset cashName\npass (sh -c 'echo x${cashName}x')", "xx");
+        assert_result("with [cashName: two] { pass (sh -c 'echo $cashName') }", "two");
+        assert_result("with [HOME: /nowhere] { true }\npass ((env)[HOME] == (echo /nowhere))", "False");
+        assert_result("export cashName=one\nf = { cashName = (echo two); unset cashName; pass $cashName }\npass (f)", "two");
+        assert_result("export cashName=one\nf = { cashName = (echo two); unset cashName }\nf\npass (sh -c 'echo x${cashName}x')", "xx");
+    }
+
+    #[test]
+    fn test_runner_directories() {
+        let _lock = DIRECTORY.lock().unwrap();
+        let original = std::env::current_dir().unwrap();
+        let path = original.to_string_lossy();
+
+        assert_result("cd /\npass (pwd)", "/");
+        assert_result(&format!("cd {}\ncd /\npass ($PWD + $OLDPWD)", path), &format!("/{}", path));
+        assert_result(&format!("cd {}\ncd /\ncd -\npass (sh -c pwd)", path), &path);
+        assert_result("CDPATH = (echo /)\ncd /usr\ncd tmp\npass (pwd)", "/tmp");
+        assert_result(&format!("cd /\npushd {}\npushd /usr\nx = (dirs)\npopd\npass ((pwd) + $x)", path), &format!("{}[/usr, {}, /]", path, path));
+        assert_result("pushd /\ntry { cd /nowhere } catch { e -> pass (echo $e.kind (pwd)) }", "IOError /");
+
+        std::env::set_current_dir(original).unwrap();
+    }
+
+    #[test]
+    fn test_runner_builtins() {
+        let mut runner = Runner::new();
+
+        runner.add_builtin("twice", Rc::new(|_: &mut Runner, arguments: Vec<Box<dyn Value>>| -> BuiltinResult {
+            Ok(StringValue::create(&arguments[1].to_string().repeat(2)))
+        }));
+
+        runner.add_builtin("broken", Rc::new(|_: &mut Runner, _: Vec<Box<dyn Value>>| -> BuiltinResult {
+            Err(ErrorValue::create(IO_ERROR, "Broken"))
+        }));
+
+        assert_eq!("abab", run(&mut runner, "pass (twice ab)").to_string());
+        assert_eq!("IOError", run(&mut runner, "try { broken } catch { e -> pass $e.kind }").to_string());
+
+        assert_result("pass = { x -> builtin pass ($x + 1) }\npass (pass 1)", "3");
+        assert_result("range = (echo 2)\npass ((range 3) == (range 0 3))", "True");
+        assert_result("try { builtin nope } catch { e -> pass $e.kind }", "NameError");
+    }
+
+    #[test]
+    fn test_interpreter() {
+        let mut interpreter = Interpreter::new();
+
+        interpreter.set_variable("base", NumberValue::create(10));
+        assert_eq!("12", interpreter.eval_str("pass ($base + 2)").unwrap().to_string());
+
+        interpreter.eval_str("add = { a, b -> pass ($a + $b + $base) }\ntotal = (add 1 2)").unwrap();
+        assert_eq!("13", interpreter.get_variable("total").unwrap().to_string());
+
+        let arguments: Vec<Box<dyn Value>> = vec![NumberValue::create(3), NumberValue::create(4)];
+        assert_eq!("17", interpreter.call_closure("add", arguments).unwrap().to_string());
+
+        assert_eq!("NameError", interpreter.call_closure("nope", vec![]).unwrap_err().kind);
+        assert_eq!("TypeError", interpreter.call_closure("total", vec![]).unwrap_err().kind);
+        assert_eq!("IOError", interpreter.eval_file("/nonexistent/script.cash").unwrap_err().kind);
+
+        let error = interpreter.eval_str("x = 1\nthrow Oops\nx = 2").unwrap_err();
+        assert_eq!("Oops", error.message);
+        assert_eq!("1", interpreter.get_variable("x").unwrap().to_string());
+
+        let error = interpreter.eval_str("x = 3\nx = (\nx = 4").unwrap_err();
+        assert_eq!("SyntaxError", error.kind);
+        assert_eq!(Some(2), error.line);
+        assert_eq!("3", interpreter.get_variable("x").unwrap().to_string());
+
+        let (mut reader, writer) = std::io::pipe().unwrap();
+        interpreter.set_stdout(Channel::Pipe(writer));
+        interpreter.eval_str("echo hello").unwrap();
+        interpreter.set_stdout(Channel::Inherit);
+
+        let mut output = String::new();
+        reader.read_to_string(&mut output).unwrap();
+        assert_eq!("hello\n", output);
+
+        let (mut reader, writer) = std::io::pipe().unwrap();
+        interpreter.set_stderr(Channel::Pipe(writer));
+        interpreter.eval_str("sh -c 'echo oops >&2'\nbreak").unwrap();
+        interpreter.set_stderr(Channel::Inherit);
+
+        let mut output = String::new();
+        reader.read_to_string(&mut output).unwrap();
+        assert_eq!("oops\nWarning > Ignoring Break outside of a loop\n", output);
+    }
+
+    #[test]
+    fn test_runner_jobs() {
+        assert_result("sleep 0.1 &", "1");
+        assert_result("sleep 0.1 &\nsleep 0.1 &", "2");
+        assert_result("sh -c 'exit 3' &\nwait %1", "3");
+        assert_result("true &\nfalse &\nwait\npass $?", "1");
+        assert_result("sleep 5 &\nkill -KILL %1\nwait", "137");
+        assert_result("sleep 0.1 &\nj = (jobs)\npass $j[0][command]", "sleep 0.1");
+        assert_result("sleep 0.1 &\nwait\njobs", "[]");
+        assert_result("sh -c 'exit 2' &\nfg", "2");
+        assert_result("try { fg } catch { e -> pass $e.kind }", "JobError");
+        assert_result("sleep 0.1 &\ntry { wait %2 } catch { e -> pass $e.kind }", "JobError");
+        assert_result("try { pass 1 & } catch { e -> pass $e.kind }", "UsageError");
+
+        // the commands that have started are
+        // killed rather than waited for
+        let started = std::time::Instant::now();
+        assert_result("try { sleep 10 | nonexistentCommand } catch { e -> pass $e.code }", "127");
+        assert_result("try { sleep 10 | nonexistentCommand & } catch { e -> pass $e.code }", "127");
+        assert!(started.elapsed() < std::time::Duration::from_secs(5));
+    }
+
+    #[test]
+    fn test_runner_traps() {
+        assert_result("hits = 0\ntrap USR1 { outerScope.hits = ($hits + 1) }\nsh -c 'kill -USR1 $PPID'\nsleep 0.1\npass $hits", "1");
+        assert_result("trap USR2 EXIT { pass 1 }\ntrap USR2\ntrap", "[EXIT: [closure]]");
+        assert_result("try { trap KILL { pass 1 } } catch { e -> pass $e.kind }", "UsageError");
+        assert_result("try { trap NOPE { pass 1 } } catch { e -> pass $e.kind }", "UsageError");
+
+        let mut runner = Runner::new();
+        run(&mut runner, "x = 1\ntrap EXIT { outerScope.x = 2; exit 3 }");
+        runner.run_exit_trap();
+        assert_eq!(3, runner.exit_code);
+        assert_eq!("2", runner.global_scope.get_value("x").unwrap().to_string());
+    }
+}
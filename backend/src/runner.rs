@@ -1,43 +1,136 @@
 use frontend::ast::*;
 use frontend::ast::nodes::*;
+use frontend::lexer::{is_glob, unescape_word, to_number_digits};
 
-use crate::value::Value;
+use crate::value::{Value, compare};
 use crate::value::none::NoneValue;
 use crate::value::number::NumberValue;
+use crate::value::float::FloatValue;
+use crate::value::boolean::BooleanValue;
 use crate::value::string::StringValue;
 use crate::value::closure::{ClosureValue, ClosureData};
 use crate::value::scope::{ScopeValue, ScopeData};
 use crate::value::provider::ProviderValue;
+use crate::value::status::StatusValue;
+use crate::value::descriptor::DescriptorValue;
+use crate::value::error::*;
+use crate::value::list::ListValue;
+use crate::value::map::MapValue;
+use crate::value::pattern::{PatternValue, Unmatched, expand_tilde};
 
-use processing::launch_pipeline;
+use crate::builtins::{Builtin, Builtins, create_builtins};
 
-use std::fs;
+use processing::{run_pipeline, OutputMode, Channel, Redirections};
+use processing::jobs::{Job, JobState, start_job, give_terminal, take_terminal};
+use processing::signals::{SIGINT, add_signal, take_signal, get_signal_name};
+
+use std::fs::{File, OpenOptions};
 
 use crate::{cast, cast_mut};
 
-use helpers::{elvis, result_or, some_or};
+use helpers::{elvis, some_or};
+
+use num_bigint::BigInt;
 
 use std::rc::Rc;
 use std::cell::RefCell;
+use std::collections::HashMap;
+
+/// A non-local jump that's in progress. The
+/// evaluation stops until someone handles it.
+#[derive(Debug)]
+pub enum Unwinding {
+    /// Stops the closest loop.
+    Break,
+    /// Goes to the next iteration
+    /// of the closest loop.
+    Continue,
+    /// Leaves the closest closure that
+    /// has been called as a command.
+    Return(Box<dyn Value>),
+    /// Aborts the current statement.
+    Error(Box<ErrorValue>),
+}
 
 pub struct Runner {
     pub value: Box<dyn Value>,
     pub command: Vec<Box<dyn Value>>,
     pub should_exit: bool,
+    /// The code the shell process
+    /// should exit with.
+    pub exit_code: i32,
     pub scope: Box<ScopeValue>,
+    /// The outermost scope, the one that
+    /// holds the environment variables.
+    /// Its exported properties are passed
+    /// to external commands.
+    pub global_scope: Box<ScopeValue>,
     pub closure_arguments: Vec<String>,
+    /// True inside `( ... )`, where the
+    /// output of external commands becomes
+    /// their value.
+    pub capture_output: bool,
+    /// What the external commands have written
+    /// so far within the innermost `( ... )`,
+    /// `None` until the first one runs.
+    pub captured_output: Option<String>,
+    /// The streams external commands
+    /// are connected to.
+    pub redirections: Redirections,
+    /// The streams the command of a pipeline
+    /// that's being evaluated is redirected to,
+    /// `None` outside of the pipelines.
+    pub command_redirections: Option<Redirections>,
+    /// The variables `with` adds to the
+    /// environment of external commands,
+    /// the innermost last.
+    pub environment_overrides: Vec<(String, String)>,
+    /// The directories `pushd` has left,
+    /// the latest last.
+    pub directory_stack: Vec<String>,
+    /// The commands implemented in Rust.
+    pub builtins: Builtins,
+    /// The pipelines that run in the background
+    /// or have been stopped, the oldest first.
+    pub jobs: Vec<Job>,
+    /// Puts every pipeline into its own process
+    /// group and gives it the terminal, so that
+    /// Ctrl-Z stops it. Only makes sense in
+    /// interactive sessions.
+    pub job_control: bool,
+    /// Whether the commands come from the user
+    /// rather than a script. Ctrl-C is only
+    /// caught without a trap in this case.
+    pub interactive: bool,
+    /// Set by `&` for the pipeline
+    /// that's about to run.
+    pub run_in_background: bool,
+    /// The closures `trap` has attached to
+    /// the signals, `0` stands for the exit.
+    pub traps: HashMap<i32, Box<ClosureValue>>,
+    pub unwinding: Option<Unwinding>,
+    /// The line the current statement
+    /// starts at.
+    pub line: usize,
+    /// The names of the closures that are
+    /// running and the lines they've been
+    /// called at, the outermost first.
+    pub call_stack: Vec<(String, usize)>,
+    /// The error that has aborted the
+    /// last top-level statement.
+    pub error: Option<Box<ErrorValue>>,
 }
 
-impl Runner {
-    pub fn new() -> Runner {
-        Runner {
-            value: NoneValue::create(),
-            command: vec![],
-            should_exit: false,
-            scope: ScopeValue::create(ScopeData::create_global()),
-            closure_arguments: vec![],
-        }
+/// `$name` wraps the values, so that they
+/// aren't treated as command names, but
+/// once passed somewhere, they are plain
+/// values again.
+pub fn unwrap_provider(mut value: Box<dyn Value>) -> Box<dyn Value> {
+    if let Some(provider) = cast_mut!(value => ProviderValue) {
+        return std::mem::replace(&mut provider.delegate, NoneValue::create());
     }
+
+    return value;
 }
 
 macro_rules! with {
@@ -62,12 +155,6 @@ macro_rules! with_command {
     };
 }
 
-macro_rules! with_scope {
-    ( $scope:expr, $this:expr => $visit_call:expr ) => {
-        with! { $this.scope => $scope => $visit_call }
-    };
-}
-
 macro_rules! with_closure_arguments {
     ( $this:expr => $visit_call:expr ) => {
         with! { $this.closure_arguments => vec![] => $visit_call }
@@ -89,36 +176,699 @@ macro_rules! extract_text {
     };
 }
 
-impl SimpleVisitor for Runner {
-    fn visit_number(&mut self, it: &mut Number) {
-        let mut result = 0;
-        let mut shift = 1;
-
-        if it.base <= 10 {
-            for that in it.value.bytes().rev() {
-                result += ((that - '0' as u8) as i32) * shift;
-                shift *= it.base as i32;
-            }
-        } else if it.base > 10 {
-            for that in it.value.bytes().rev() {
-                if ('0'..='9').contains(&(that as char)) {
-                    result += ((that - '0' as u8) as i32) * shift;
-                } else if ('a'..='z').contains(&(that as char)) {
-                    result += ((that - 'a' as u8) as i32 + 10) * shift;
-                } else {
-                    result += ((that - 'A' as u8) as i32 + 10) * shift;
+/// The number as it's been written,
+/// if the node is a number.
+fn extract_number(node: &mut Box<dyn Node>) -> Option<String> {
+    let mut result = None;
+
+    let mut extractor = Extractor::new(|it: &mut Number| {
+        result = Some(it.value.clone());
+    });
+
+    node.accept_simple_visitor(&mut extractor);
+    result
+}
+
+/// Checks if the node is written as `@target`.
+fn is_descriptor(node: &mut Box<dyn Node>) -> bool {
+    let mut result = false;
+
+    let mut extractor = Extractor::new(|it: &mut Unary| {
+        result = extract_text!(it.operator).as_deref() == Some("@");
+    });
+
+    node.accept_simple_visitor(&mut extractor);
+    result
+}
+
+fn is_redirection(operator: &str) -> bool {
+    matches!(operator, "<" | ">" | ">>" | "!>" | "!>>")
+}
+
+/// The stream the operator redirects.
+fn select_channel<'a>(redirections: &'a mut Redirections, operator: &str) -> &'a mut Channel {
+    match operator {
+        "<" => &mut redirections.input,
+        ">" | ">>" => &mut redirections.output,
+        _ => &mut redirections.error,
+    }
+}
+
+/// Opens the channel the descriptor refers to.
+/// `None` means the stream stays where it is,
+/// like in `> @1`.
+fn open_channel(operator: &str, descriptor: &DescriptorValue) -> Result<Option<Channel>, Box<ErrorValue>> {
+    let own = match operator {
+        "<" => 0,
+        ">" | ">>" => 1,
+        _ => 2,
+    };
+
+    if let Some(standard) = descriptor.get_standard() {
+        return match (own, standard) {
+            (it, that) if it == that => Ok(None),
+            (1, 2) => Ok(Some(Channel::Error)),
+            (2, 1) => Ok(Some(Channel::Output)),
+            _ => Err(ErrorValue::create(USAGE_ERROR, &format!("Malformed redirection > Can't connect @{} to @{}", own, standard))),
+        };
+    }
+
+    if descriptor.is_null() {
+        return Ok(Some(Channel::Null));
+    }
+
+    let path = &descriptor.target;
+
+    let file = match operator {
+        "<" => File::open(path),
+        ">" | "!>" => File::create(path),
+        _ => OpenOptions::new().append(true).create(true).open(path),
+    };
+
+    match file {
+        Ok(it) => Ok(Some(Channel::File(it))),
+        Err(error) => Err(ErrorValue::create(IO_ERROR, &format!("Couldn't open the file > {:?} > {}", path, error))),
+    }
+}
+
+impl Runner {
+    pub fn new() -> Runner {
+        let global = ScopeData::create_global();
+        global.borrow_mut().set_value("?", StatusValue::create(0, None));
+
+        Runner {
+            value: NoneValue::create(),
+            command: vec![],
+            should_exit: false,
+            exit_code: 0,
+            scope: ScopeValue::create(global.clone()),
+            global_scope: ScopeValue::create(global),
+            closure_arguments: vec![],
+            capture_output: false,
+            captured_output: None,
+            redirections: Redirections::new(),
+            command_redirections: None,
+            environment_overrides: vec![],
+            directory_stack: vec![],
+            builtins: create_builtins(),
+            jobs: vec![],
+            job_control: false,
+            interactive: false,
+            run_in_background: false,
+            traps: HashMap::new(),
+            unwinding: None,
+            line: 0,
+            call_stack: vec![],
+            error: None,
+        }
+    }
+
+    /// Makes the status the result of the
+    /// current pipeline and remembers it
+    /// as `?`.
+    pub fn set_status(&mut self, status: Box<StatusValue>) {
+        self.exit_code = status.code;
+        self.global_scope.set_value("?", status.clone());
+        self.value = status;
+    }
+
+    /// Evaluates the word of `@target`
+    /// without running it as a command.
+    fn describe(&mut self, target: &mut Box<dyn Node>) -> Option<Vec<String>> {
+        let mut words = None;
+
+        let mut extractor = Extractor::new(|it: &mut Command| {
+            words = Some(with_command! { self => it.accept_simple_visitor(self) });
+        });
+
+        target.accept_simple_visitor(&mut extractor);
+
+        let words = words?.iter()
+            .map(|it| it.to_string())
+            .collect::<Vec<String>>();
+
+        Some(words)
+    }
+
+    /// Runs the `lefter` with one of its
+    /// streams connected to the `@target`.
+    fn redirect(&mut self, operator: &str, it: &mut Binary) {
+        let righter = with_value! { self => it.righter.accept_simple_visitor(self) };
+
+        if self.is_unwinding() {
+            return;
+        }
+
+        let descriptor = some_or! { cast!(righter => DescriptorValue) => {
+            self.value = self.fail(USAGE_ERROR, &format!("Malformed redirection > Not a descriptor > {:?}", righter.to_string()));
+            return;
+        }};
+
+        let channel = match open_channel(operator, descriptor) {
+            Ok(Some(it)) => it,
+            Ok(None) => {
+                self.value = with_value! { self => it.lefter.accept_simple_visitor(self) };
+                return;
+            }
+            Err(error) => {
+                self.value = self.raise(error);
+                return;
+            }
+        };
+
+        // within a pipeline, only the
+        // command itself is redirected
+        if let Some(own) = self.command_redirections.as_mut() {
+            *select_channel(own, operator) = channel;
+            self.value = with_value! { self => it.lefter.accept_simple_visitor(self) };
+            return;
+        }
+
+        let old = std::mem::replace(select_channel(&mut self.redirections, operator), channel);
+        self.value = with_value! { self => it.lefter.accept_simple_visitor(self) };
+        *select_channel(&mut self.redirections, operator) = old;
+    }
+
+    /// Runs the `action` with the streams of the
+    /// shell replaced by the ones the command
+    /// has been redirected to.
+    fn with_redirections(&mut self, own: Redirections, action: impl FnOnce(&mut Self) -> Box<dyn Value>) -> Box<dyn Value> {
+        let Redirections { input, output, error } = own;
+        let mut replaced = vec![];
+
+        for (operator, channel) in [("<", input), (">", output), ("!>", error)] {
+            if !channel.is_inherit() {
+                replaced.push((operator, std::mem::replace(select_channel(&mut self.redirections, operator), channel)));
+            }
+        }
+
+        let value = action(self);
+
+        for (operator, channel) in replaced {
+            *select_channel(&mut self.redirections, operator) = channel;
+        }
+
+        value
+    }
+
+    /// Handles `$target[index] = value`. Returns
+    /// false if the receiver is not a subscript.
+    fn assign_subscript(&mut self, it: &mut Assignment) -> bool {
+        let mut receiver = None;
+
+        let mut extractor = Extractor::new(|that: &mut Subscript| {
+            let target = with_value! { self => that.target.accept_simple_visitor(self) };
+            let index = with_value! { self => that.index.accept_simple_visitor(self) };
+            receiver = Some((unwrap_provider(target), unwrap_provider(index)));
+        });
+
+        it.receiver.accept_simple_visitor(&mut extractor);
+
+        let (target, index) = some_or! { receiver => return false };
+
+        if self.is_unwinding() {
+            return true;
+        }
+
+        let value = unwrap_provider(with_value! { self => it.value.accept_simple_visitor(self) });
+
+        if self.is_unwinding() {
+            return true;
+        }
+
+        let result = target.set(&[index], value);
+        self.value = self.check(result);
+        true
+    }
+
+    /// Prints the message to the stderr of
+    /// the shell, wherever it's been set to.
+    pub fn print_error(&self, message: &str) {
+        let text = format!("{}\n", message);
+
+        let result = match &self.redirections.error {
+            Channel::Output => self.redirections.output.write_all(text.as_bytes(), &mut std::io::stdout()),
+            it => it.write_all(text.as_bytes(), &mut std::io::stderr()),
+        };
+
+        // there's nowhere else to report it
+        let _ = result;
+    }
+
+    /// Runs a top-level statement.
+    pub fn run(&mut self, statement: &mut Box<dyn Node>) {
+        self.execute(statement);
+
+        if let Some(error) = &self.error {
+            self.print_error(&error.report());
+        }
+    }
+
+    /// Like `run()`, but leaves reporting
+    /// the error to the caller.
+    pub fn execute(&mut self, statement: &mut Box<dyn Node>) {
+        self.error = None;
+        self.call_stack.clear();
+
+        statement.accept_simple_visitor(self);
+        self.finish_unwinding();
+    }
+
+    /// Fails the top-level statement that
+    /// couldn't be parsed, so that it's
+    /// reported like the other errors.
+    pub fn fail_syntax(&mut self, message: &str) {
+        self.error = None;
+        self.call_stack.clear();
+        self.fail(SYNTAX_ERROR, message);
+        self.finish_unwinding();
+    }
+
+    /// Handles the unwinding that has reached
+    /// the top level. An error ends up in `error`.
+    pub fn finish_unwinding(&mut self) {
+        match self.unwinding.take() {
+            Some(Unwinding::Return(..)) => {
+                self.print_error("Warning > Ignoring return outside of a closure");
+            }
+            Some(Unwinding::Error(error)) => {
+                self.set_status(StatusValue::create(error.code, None));
+                self.value = NoneValue::create();
+                self.error = Some(error);
+            }
+            Some(unwinding) => {
+                self.print_error(&format!("Warning > Ignoring {:?} outside of a loop", unwinding));
+            }
+            None => {}
+        }
+    }
+
+    /// Starts unwinding with the error. If it
+    /// has no location yet, it gets the current one.
+    pub fn raise(&mut self, mut error: Box<ErrorValue>) -> Box<dyn Value> {
+        if error.line.is_none() {
+            error.line = Some(self.line);
+            error.trace = self.call_stack.iter()
+                .rev()
+                .map(|(name, line)| format!("{} called at line {}", name, line))
+                .collect();
+        }
+
+        self.unwinding = Some(Unwinding::Error(error));
+        NoneValue::create()
+    }
+
+    pub fn fail(&mut self, kind: &str, message: &str) -> Box<dyn Value> {
+        self.raise(ErrorValue::create(kind, message))
+    }
+
+    /// Raises the value if it's an error,
+    /// like the ones returned by operators.
+    fn check(&mut self, mut value: Box<dyn Value>) -> Box<dyn Value> {
+        if let Some(error) = cast_mut!(value => ErrorValue) {
+            let error = Box::new(std::mem::replace(error, ErrorValue::new("", "")));
+            return self.raise(error);
+        }
+
+        return value;
+    }
+
+    /// True if the evaluation of the
+    /// current statement must stop.
+    pub fn is_unwinding(&self) -> bool {
+        self.unwinding.is_some() || self.should_exit
+    }
+
+    /// `unmatchedGlobs` decides what happens
+    /// to the globs that match no files.
+    fn get_unmatched_policy(&mut self) -> Option<Unmatched> {
+        let name = match self.scope.resolve("unmatchedGlobs") {
+            Some(it) => it.to_string(),
+            None => return Some(Unmatched::Keep),
+        };
+
+        if let Some(it) = Unmatched::from_name(&name) {
+            return Some(it);
+        }
+
+        self.value = self.fail(USAGE_ERROR, &format!(
+            "unmatchedGlobs must be error, keep or empty > {:?}", name
+        ));
+        None
+    }
+
+    /// Whether the command name refers to
+    /// neither a variable nor a builtin.
+    fn is_external(&mut self, name: &dyn Value) -> bool {
+        if cast!(name => StringValue).is_none() && cast!(name => PatternValue).is_none() {
+            return false;
+        }
+
+        let name = name.to_string();
+        self.scope.resolve(&name).is_none() && !self.builtins.contains_key(&name)
+    }
+
+    /// The arguments of an external command.
+    /// Globs are replaced with the paths
+    /// they match.
+    fn expand_arguments(&mut self, command: &[Box<dyn Value>]) -> Option<Vec<String>> {
+        let mut arguments = vec![command[0].to_string()];
+        let mut policy = None;
+
+        for value in &command[1..] {
+            let pattern = some_or! { cast!(value => PatternValue) => {
+                arguments.push(value.to_string());
+                continue;
+            }};
+
+            if policy.is_none() {
+                policy = Some(self.get_unmatched_policy()?);
+            }
+
+            match pattern.expand(policy.unwrap_or(Unmatched::Keep)) {
+                Ok(it) => arguments.extend(it),
+                Err(it) => {
+                    self.value = self.fail(GLOB_ERROR, &format!("No matches found > {:?}", it));
+                    return None;
                 }
-                shift *= it.base as i32;
             }
         }
 
-        self.value = NumberValue::create(result);
+        Some(arguments)
+    }
+
+    /// Calls the closure in a fresh child scope
+    /// of the one it has been created in. A `return`
+    /// inside of it provides the result.
+    pub fn call_closure(&mut self, closure: &ClosureValue, values: Vec<Box<dyn Value>>) -> Box<dyn Value> {
+        let result = self.enter_closure(closure, values, vec![]);
+
+        match self.unwinding.take() {
+            Some(Unwinding::Return(value)) => value,
+            other => {
+                self.unwinding = other;
+                result
+            }
+        }
+    }
+
+    /// Runs the closure body in a fresh scope with
+    /// the given variables declared. Unlike `call_closure()`,
+    /// `return` passes through, so that the builtins
+    /// can use it for running branches and bodies.
+    pub fn enter_closure(
+        &mut self,
+        closure: &ClosureValue,
+        values: Vec<Box<dyn Value>>,
+        variables: Vec<(String, Box<dyn Value>)>
+    ) -> Box<dyn Value> {
+        // the body is copied, so that the closure
+        // isn't borrowed while it's running, and
+        // can call itself
+        let (names, mut body, parent) = {
+            let mut data = closure.data.borrow_mut();
+
+            let names = with_closure_arguments! {
+                self => data.arguments.accept_simple_visitor(self)
+            };
+
+            (names, replicate(&mut data.body), data.scope.clone())
+        };
+
+        if self.is_unwinding() {
+            return NoneValue::create();
+        }
+
+        let mut scope = ScopeValue::create(ScopeData::create(Some(parent)));
+        let mut values = values.into_iter();
+
+        for name in &names {
+            let value = some_or! { values.next() => NoneValue::create() };
+            scope.set_value(name, unwrap_provider(value));
+        }
+
+        for (name, value) in variables {
+            scope.set_value(&name, value);
+        }
+
+        let old = std::mem::replace(&mut self.scope, scope);
+        let old_line = self.line;
+        let result = with_value! { self => body.accept_simple_visitor(self) };
+        self.line = old_line;
+        self.scope = old;
+
+        return result;
+    }
+
+    /// Closures are called when the condition
+    /// is reached, other values are used as is.
+    pub fn check_condition(&mut self, condition: Box<dyn Value>) -> bool {
+        let condition = unwrap_provider(condition);
+
+        if let Some(closure) = cast!(condition => ClosureValue) {
+            return self.enter_closure(closure, vec![], vec![]).to_boolean();
+        }
+
+        return condition.to_boolean();
+    }
+
+    /// The exported variables along
+    /// with the ones added by `with`.
+    pub fn get_environment(&self) -> Vec<(String, String)> {
+        let mut result = self.global_scope.data.borrow().get_environment();
+
+        for (key, value) in &self.environment_overrides {
+            result.retain(|(it, _)| it != key);
+            result.push((key.clone(), value.clone()));
+        }
+
+        result
+    }
+
+    /// Makes the builtin available under the name,
+    /// replacing the one that's already there.
+    pub fn add_builtin(&mut self, name: &str, builtin: Rc<dyn Builtin>) {
+        self.builtins.insert(name.to_owned(), builtin);
+    }
+
+    /// Runs the builtin and raises
+    /// the error it returns.
+    fn call_builtin(&mut self, builtin: Rc<dyn Builtin>, command: Vec<Box<dyn Value>>) -> Box<dyn Value> {
+        match builtin.call(self, command) {
+            Ok(it) => it,
+            Err(error) => self.raise(error),
+        }
+    }
+
+    /// The id after the greatest one in use.
+    fn next_job_id(&self) -> usize {
+        self.jobs.iter().map(|it| it.id).max().unwrap_or(0) + 1
+    }
+
+    /// Launches the pipeline in its own process
+    /// group. In the background its value is the
+    /// job id, otherwise it's waited for.
+    fn launch_job(&mut self, commands: &[Vec<String>], redirections: &[Redirections], background: bool) {
+        let environment = self.get_environment();
+
+        let text = commands.iter()
+            .map(|it| it.join(" "))
+            .collect::<Vec<String>>()
+            .join(" | ");
+
+        let job = match start_job(self.next_job_id(), &text, &self.redirections, &environment, commands, redirections, !background) {
+            Ok(it) => it,
+            Err(error) => {
+                let mut error = ErrorValue::create(COMMAND_ERROR, &format!("Couldn't run the pipeline > {:?} > {}", &commands[0][0], error));
+                error.code = 127;
+                self.value = self.raise(error);
+                return;
+            }
+        };
+
+        if !background {
+            let status = self.wait_in_foreground(job);
+            self.set_status(status);
+            return;
+        }
+
+        if self.job_control {
+            self.print_error(&format!("[{}] {}", job.id, job.group));
+        }
+
+        let id = job.id;
+        self.jobs.push(job);
+        self.set_status(StatusValue::create(0, None));
+        self.value = NumberValue::create(id as i32);
+    }
+
+    /// Gives the job the terminal and waits for it.
+    /// If it gets stopped, it goes to `jobs`.
+    pub fn wait_in_foreground(&mut self, mut job: Job) -> Box<StatusValue> {
+        if self.job_control {
+            give_terminal(job.group);
+        }
+
+        job.wait();
+
+        if self.job_control {
+            take_terminal();
+        }
+
+        match job.state {
+            JobState::Done(status) => {
+                let status = StatusValue::from_exit_status(&status);
+
+                // the shell isn't in the foreground
+                // group, so it hasn't seen the Ctrl-C
+                if self.job_control && status.signal == Some(SIGINT) {
+                    add_signal(SIGINT);
+                }
+
+                status
+            }
+            JobState::Stopped(signal) => {
+                self.print_error(&format!("[{}] Stopped {}", job.id, job.command));
+                self.jobs.push(job);
+                StatusValue::create(128 + signal, Some(signal))
+            }
+            JobState::Running => StatusValue::create(0, None),
+        }
+    }
+
+    /// Runs the traps of the signals that have
+    /// arrived. Ctrl-C without a trap interrupts
+    /// the current statement.
+    pub fn check_signals(&mut self) {
+        let mut signals = self.traps.keys()
+            .cloned()
+            .filter(|it| *it != 0 && *it != SIGINT)
+            .collect::<Vec<i32>>();
+
+        signals.sort();
+        signals.insert(0, SIGINT);
+
+        for signal in signals {
+            // the rest waits for the next check
+            if self.is_unwinding() {
+                return;
+            }
+
+            if !take_signal(signal) {
+                continue;
+            }
+
+            if let Some(trap) = self.traps.get(&signal).map(|it| ClosureValue::create(it.data.clone())) {
+                self.run_trap(&trap, &get_signal_name(signal));
+            } else {
+                let mut error = ErrorValue::create(INTERRUPT_ERROR, "Interrupted");
+                error.code = 128 + SIGINT;
+                self.raise(error);
+            }
+        }
+    }
+
+    /// Calls the trap keeping the value
+    /// of the command it has interrupted.
+    fn run_trap(&mut self, trap: &ClosureValue, name: &str) {
+        let value = std::mem::replace(&mut self.value, NoneValue::create());
+
+        self.call_stack.push((format!("trap {}", name), self.line));
+        self.call_closure(trap, vec![]);
+        self.call_stack.pop();
+
+        self.value = value;
+    }
+
+    /// Runs the `EXIT` trap once, right
+    /// before the shell exits. `exit` inside
+    /// of it replaces the exit code.
+    pub fn run_exit_trap(&mut self) {
+        let trap = some_or! { self.traps.remove(&0) => return };
+        let exit_code = self.exit_code;
+
+        self.should_exit = false;
+        self.error = None;
+        self.call_stack.clear();
+
+        self.run_trap(&trap, "EXIT");
+        self.finish_unwinding();
+
+        if let Some(error) = &self.error {
+            self.print_error(&error.report());
+        }
+
+        if !self.should_exit {
+            self.exit_code = exit_code;
+        }
+
+        self.should_exit = true;
+    }
+
+    /// Announces the background jobs that
+    /// have finished and forgets them.
+    pub fn report_jobs(&mut self) {
+        for it in &mut self.jobs {
+            it.update();
+        }
+
+        let (done, running) = std::mem::take(&mut self.jobs)
+            .into_iter()
+            .partition::<Vec<Job>, _>(|it| matches!(it.state, JobState::Done(..)));
+
+        self.jobs = running;
+
+        for it in done {
+            self.print_error(&format!("[{}] {} {}", it.id, it.state.describe(), it.command));
+        }
+    }
+
+    /// Binds the script arguments to `0`, `1`, ...
+    /// the same way the closure arguments are bound,
+    /// so they can be accessed via `$0`, `$1`, ...
+    pub fn set_arguments(&mut self, arguments: &[String]) {
+        for (index, it) in arguments.iter().enumerate() {
+            self.scope.set_value(&index.to_string(), StringValue::create(it));
+        }
+
+        let count = arguments.len() as i32 - 1;
+        self.scope.set_value("argumentsCount", NumberValue::create(std::cmp::max(count, 0)));
+    }
+}
+
+impl SimpleVisitor for Runner {
+    /// Decimal numbers with a dot or
+    /// an exponent are floats.
+    fn visit_number(&mut self, it: &mut Number) {
+        let digits = to_number_digits(&it.value);
+        let is_float = it.base == 10 && digits.contains(['.', 'e', 'E']);
+
+        let result: Option<Box<dyn Value>> = if is_float {
+            digits.parse::<f64>().ok().map(|it| FloatValue::create(it) as Box<dyn Value>)
+        } else {
+            BigInt::parse_bytes(digits.as_bytes(), it.base as u32).map(|it| NumberValue::create_big(it) as Box<dyn Value>)
+        };
+
+        self.value = some_or! { result => {
+            self.fail(TYPE_ERROR, &format!("Malformed number > {:?}", &it.value))
+        }};
     }
 
     fn visit_text(&mut self, it: &mut Text) {
         self.value = StringValue::create(&it.value);
     }
 
+    fn visit_pattern(&mut self, it: &mut Pattern) {
+        let home = self.scope.resolve("HOME").map(|it| it.to_string());
+        let value = expand_tilde(&it.value, home);
+
+        if is_glob(&value) {
+            self.value = PatternValue::create(&value);
+        } else {
+            self.value = StringValue::create(&unescape_word(&value));
+        }
+    }
+
     fn visit_text_parts(&mut self, it: &mut TextParts) {
         let mut result = String::new();
 
@@ -131,91 +881,118 @@ impl SimpleVisitor for Runner {
     }
 
     fn visit_command(&mut self, it: &mut Command) {
+        // the pipelines within the arguments
+        // have redirections of their own
+        let own = self.command_redirections.take();
+        let mut is_external = false;
+
         for that in &mut it.arguments {
-            let resolved = with_value! { self => that.accept_simple_visitor(self) };
+            // external commands get the numbers
+            // as they've been written, like
+            // `2021_01_01` or `1.50`
+            let number = if is_external {
+                extract_number(that)
+            } else {
+                None
+            };
+
+            let resolved = match number {
+                Some(text) => StringValue::create(&text),
+                None => with_value! { self => that.accept_simple_visitor(self) },
+            };
+
+            if self.is_unwinding() {
+                break;
+            }
+
+            if self.command.is_empty() {
+                is_external = self.is_external(resolved.as_ref());
+            }
+
             self.command.push(resolved);
         }
+
+        self.command_redirections = own;
     }
 
     fn visit_pipeline(&mut self, it: &mut Pipeline) {
+        // the pipelines inside of the arguments
+        // mustn't run in the background
+        let background = std::mem::replace(&mut self.run_in_background, false);
         let mut commands = vec![];
+        let mut redirections = vec![];
+
+        self.check_signals();
+
+        if self.is_unwinding() {
+            return;
+        }
 
         for that in &mut it.commands {
+            let old = std::mem::replace(&mut self.command_redirections, Some(Redirections::new()));
             let mut command = with_command! { self => that.accept_simple_visitor(self) };
-            let mut arguments = vec![];
+            let own = std::mem::replace(&mut self.command_redirections, old).unwrap_or_else(Redirections::new);
 
-            for value in &command {
-                arguments.push(value.to_string());
+            if self.is_unwinding() {
+                return;
             }
 
-            if arguments.is_empty() {
-                self.value = NoneValue::create();
-                println!("Error > Empty command in pipeline");
+            if command.is_empty() {
+                self.value = self.fail(USAGE_ERROR, "Empty command in pipeline");
                 return;
             }
 
-            if arguments[0] == "exit" {
-                self.should_exit = true;
-                self.value = NoneValue::create();
-                return;
+            if let Some(pattern) = cast!(&command[0] => PatternValue) {
+                command[0] = StringValue::create(&pattern.to_string());
             }
 
-            if arguments[0] == "pass" {
-                self.value = if command.len() >= 2 {
-                    command.remove(1)
-                } else {
-                    NoneValue::create()
-                };
-                return;
+            let mut arguments = vec![];
+
+            for value in &command {
+                arguments.push(value.to_string());
             }
 
             if let None = cast!(&command[0] => StringValue) {
-                let mut value = command.remove(0);
-                if let Some(provider) = cast_mut!(value => ProviderValue) {
-                    self.value = std::mem::replace(&mut provider.delegate, NoneValue::create());
-                } else {
-                    self.value = value;
+                if command.len() > 1 {
+                    self.value = self.fail(USAGE_ERROR, &format!("Not a command > {:?} can't take arguments", arguments[0]));
+                    return;
                 }
+
+                self.value = unwrap_provider(command.remove(0));
                 return;
             }
 
-            if let Some(mut value) = self.scope.resolve(&command[0].to_string()) {
-                if let Some(closure) = cast_mut!(value => ClosureValue) {
-                    let mut data = closure.data.borrow_mut();
-                    let mut scope = ScopeValue::create(data.scope.clone());
-
-                    let arguments = with_closure_arguments! {
-                        self => data.arguments.accept_simple_visitor(self)
-                    };
-
-                    let minimum = std::cmp::min(arguments.len(), command.len() - 1);
+            // closures shadow builtins, builtins
+            // shadow other variables, and all of
+            // them shadow external commands
+            let resolved = self.scope.resolve(&arguments[0]);
 
-                    for index in 0..minimum {
-                        let mut value = command.remove(1);
-
-                        if let Some(provider) = cast_mut!(value => ProviderValue) {
-                            value = std::mem::replace(&mut provider.delegate, NoneValue::create());
-                        }
+            if background && (resolved.is_some() || self.builtins.contains_key(&arguments[0])) {
+                self.value = self.fail(USAGE_ERROR, "Only external commands can run in the background");
+                return;
+            }
 
-                        scope.set_value(&arguments[index], value);
-                    }
+            if let Some(closure) = resolved.as_ref().and_then(|it| cast!(it => ClosureValue)) {
+                command.remove(0);
+                self.call_stack.push((arguments[0].clone(), self.line));
+                self.value = self.with_redirections(own, |this| this.call_closure(closure, command));
+                self.call_stack.pop();
+                return;
+            }
 
-                    for index in minimum..arguments.len() {
-                        scope.set_value(&arguments[index], NoneValue::create());
-                    }
+            if let Some(builtin) = self.builtins.get(&arguments[0]).cloned() {
+                self.value = self.with_redirections(own, |this| this.call_builtin(builtin, command));
+                return;
+            }
 
-                    with_scope! { scope, self =>
-                        self.value = with_value! { self =>
-                            data.body.accept_simple_visitor(self)
-                        }
-                    };
-                } else {
-                    self.value = value;
-                }
+            if let Some(value) = resolved {
+                self.value = value;
                 return;
             }
 
+            let arguments = some_or! { self.expand_arguments(&command) => return };
             commands.push(arguments);
+            redirections.push(own);
         }
 
         if commands.is_empty() {
@@ -223,24 +1000,64 @@ impl SimpleVisitor for Runner {
             return;
         }
 
-        let maybe_child = launch_pipeline::<fs::File, fs::File>(None, None, &commands);
-
-        let child = result_or! { maybe_child => {
-            self.value = NoneValue::create();
-            println!("Error > Command spawn a child");
+        if background || self.job_control && !self.capture_output {
+            self.launch_job(&commands, &redirections, background);
+            self.check_signals();
             return;
-        }};
+        }
 
-        let maybe_result = child.wait_with_output();
+        let mode = if self.capture_output {
+            OutputMode::Capture
+        } else {
+            OutputMode::Inherit
+        };
 
-        let result = result_or! { maybe_result => {
-            self.value = NoneValue::create();
-            println!("Error > Couln't capture output");
-            return;
-        }};
+        let environment = self.get_environment();
 
-        let _output = String::from_utf8_lossy(&result.stdout);
-        self.value = NoneValue::create();
+        let result = match run_pipeline(mode, &self.redirections, &environment, &commands, &redirections) {
+            Ok(it) => it,
+            Err(error) => {
+                let mut error = ErrorValue::create(COMMAND_ERROR, &format!("Couldn't run the pipeline > {:?} > {}", &commands[0][0], error));
+                error.code = 127;
+                self.value = self.raise(error);
+                return;
+            }
+        };
+
+        self.set_status(StatusValue::from_exit_status(&result.status));
+
+        if self.capture_output {
+            let output = self.captured_output.get_or_insert_with(String::new);
+            *output += &String::from_utf8_lossy(&result.output);
+            self.value = StringValue::create(output.trim_end_matches(&['\n', '\r'][..]));
+        }
+
+        self.check_signals();
+    }
+
+    fn visit_substitution(&mut self, it: &mut Substitution) {
+        let old = std::mem::replace(&mut self.capture_output, true);
+        let old_captured = self.captured_output.take();
+        let old_output = std::mem::replace(&mut self.redirections.output, Channel::Inherit);
+        let mut value = unwrap_provider(with_value! { self => it.target.accept_simple_visitor(self) });
+        self.redirections.output = old_output;
+        self.capture_output = old;
+
+        // all the output counts, not only
+        // the one of the last pipeline
+        if let Some(output) = std::mem::replace(&mut self.captured_output, old_captured) {
+            if !self.is_unwinding() {
+                value = StringValue::create(output.trim_end_matches(&['\n', '\r'][..]));
+            }
+        }
+
+        // just like `$name`, the result is a value
+        // rather than a command name
+        if let Some(..) = cast!(value => ClosureValue) {
+            self.value = value;
+        } else {
+            self.value = ProviderValue::create(value);
+        }
     }
 
     // fn visit_accessor(&mut self, it: &mut Accessor) {
@@ -264,9 +1081,12 @@ impl SimpleVisitor for Runner {
         let receiver_value = with_value! { self => it.target.accept_simple_visitor(self) };
         let receiver = receiver_value.to_string();
 
+        if self.is_unwinding() {
+            return;
+        }
+
         if receiver.is_empty() {
-            println!("Warning > Accessing the provider > Receiver name is empty > {:?}", &receiver);
-            self.value = NoneValue::create();
+            self.value = self.fail(USAGE_ERROR, "Malformed provider > The name is empty");
             return;
         }
 
@@ -277,43 +1097,141 @@ impl SimpleVisitor for Runner {
                 self.value = ProviderValue::create(value);
             }
         } else {
-            println!("Warning > Accessing the provider > Unresolved property name > {:?}", &receiver);
+            self.value = self.fail(NAME_ERROR, &format!("Unresolved name > {:?}", &receiver));
         }
     }
 
+    fn visit_interpolation(&mut self, it: &mut Interpolation) {
+        let name = with_value! { self => it.target.accept_simple_visitor(self) }.to_string();
+        let parts = name.split('.').collect::<Vec<&str>>();
+
+        let mut value = some_or! { self.scope.resolve(parts[0]) => {
+            self.value = self.fail(NAME_ERROR, &format!("Unresolved name > {:?}", parts[0]));
+            return;
+        }};
+
+        // the dots after something other than
+        // a scope are a part of the text,
+        // as in `"$name.txt"`
+        let mut rest = String::new();
+
+        for (index, part) in parts.iter().enumerate().skip(1) {
+            let field = match cast_mut!(value => ScopeValue) {
+                Some(scope) => scope.get_value(part),
+                None => {
+                    rest = ".".to_owned() + &parts[index..].join(".");
+                    break;
+                }
+            };
+
+            value = some_or! { field => {
+                self.value = self.fail(NAME_ERROR, &format!("Unresolved name > {:?}", parts[..=index].join(".")));
+                return;
+            }};
+        }
+
+        self.value = StringValue::create(&(value.to_string() + &rest));
+    }
+
     fn visit_unary(&mut self, it: &mut Unary) {
         let operator = with_value! { self => it.operator.accept_simple_visitor(self) };
-        let target = with_value! { self => it.target.accept_simple_visitor(self) };
 
-        self.value = match &*operator.to_string() {
+        if operator.to_string() == "@" {
+            let words = some_or! { self.describe(&mut it.target) => {
+                self.value = self.fail(USAGE_ERROR, "Malformed descriptor > The target is not a single command");
+                return;
+            }};
+
+            if self.is_unwinding() {
+                return;
+            }
+
+            self.value = match &words[..] {
+                [target] => DescriptorValue::create(target),
+                _ => self.fail(USAGE_ERROR, &format!("Malformed descriptor > Expected a single target > {:?}", words.join(" "))),
+            };
+            return;
+        }
+
+        let target = unwrap_provider(with_value! { self => it.target.accept_simple_visitor(self) });
+
+        if self.is_unwinding() {
+            return;
+        }
+
+        let result = match &*operator.to_string() {
             "+" => target.unary_plus(),
             "-" => target.unary_minus(),
             "not" => target.not(),
             "$" => StringValue::create("[getter]"),
-            "@" => StringValue::create("[descriptor]"),
-            _ => NoneValue::create()
-        }
+            other => ErrorValue::create(USAGE_ERROR, &format!("Unknown operator > {:?}", other)),
+        };
+
+        self.value = self.check(result);
     }
 
     fn visit_binary(&mut self, it: &mut Binary) {
-        let operator = with_value! { self => it.operator.accept_simple_visitor(self) };
-        let lefter = with_value! { self => it.lefter.accept_simple_visitor(self) };
-        let righter = with_value! { self => it.righter.accept_simple_visitor(self) };
+        let operator = with_value! { self => it.operator.accept_simple_visitor(self) }.to_string();
 
-        self.value = match &*operator.to_string() {
+        if is_redirection(&operator) && is_descriptor(&mut it.righter) {
+            self.redirect(&operator, it);
+            return;
+        }
+
+        let lefter = unwrap_provider(with_value! { self => it.lefter.accept_simple_visitor(self) });
+
+        if self.is_unwinding() {
+            return;
+        }
+
+        // the righter side isn't needed
+        // if the lefter one decides
+        match &*operator {
+            "and" if !lefter.to_boolean() => {
+                self.value = BooleanValue::create(false);
+                return;
+            }
+            "or" if lefter.to_boolean() => {
+                self.value = BooleanValue::create(true);
+                return;
+            }
+            _ => {}
+        }
+
+        let righter = unwrap_provider(with_value! { self => it.righter.accept_simple_visitor(self) });
+
+        if self.is_unwinding() {
+            return;
+        }
+
+        let result = match &*operator {
             "+" => lefter.plus(righter),
             "-" => lefter.minus(righter),
             "*" => lefter.times(righter),
             "/" => lefter.divide(righter),
+            "%" => lefter.reminder(righter),
             "^" => lefter.power(righter),
-            _ => NoneValue::create()
-        }
+            "<" | ">" | "<=" | ">=" => compare(&operator, lefter, righter),
+            "==" => lefter.equals(righter),
+            "!=" => BooleanValue::create(!lefter.equals(righter).value),
+            "in" => righter.contains(lefter),
+            "and" | "or" => BooleanValue::create(righter.to_boolean()),
+            "nand" => BooleanValue::create(!(lefter.to_boolean() && righter.to_boolean())),
+            "xor" => BooleanValue::create(lefter.to_boolean() != righter.to_boolean()),
+            "xnor" => BooleanValue::create(lefter.to_boolean() == righter.to_boolean()),
+            other => ErrorValue::create(USAGE_ERROR, &format!("Unknown operator > {:?}", other)),
+        };
+
+        self.value = self.check(result);
     }
 
     fn visit_assignment(&mut self, it: &mut Assignment) {
+        if self.assign_subscript(it) {
+            return;
+        }
+
         let receiver = some_or! { extract_text!(it.receiver) => {
-            println!("Warning > Assignment ignored > Receiver is not a valid name");
-            self.value = NoneValue::create();
+            self.value = self.fail(USAGE_ERROR, "Malformed assignment > The receiver is not a valid name");
             return;
         }};
 
@@ -325,31 +1243,43 @@ impl SimpleVisitor for Runner {
         let name = prefix.remove(prefix.len() - 1);
 
         if name.is_empty() {
-            println!("Warning > Assignment ignored > Receiver name is empty > {:?}", &receiver);
-            self.value = NoneValue::create();
+            self.value = self.fail(USAGE_ERROR, &format!("Malformed assignment > The receiver name is empty > {:?}", &receiver));
             return;
         }
 
         let mut receiver_scope = ScopeValue::create(self.scope.data.clone());
 
+        // `outerScope.name = value` updates a variable
+        // of an enclosing scope, while a plain
+        // assignment always declares a local one
+        let is_outer = prefix.len() == 1 && prefix[0] == "outerScope";
+
         if !prefix.is_empty() {
             if let Some(mut value) = self.scope.resolve_parts(&prefix) {
                 if let Some(scope) = cast_mut!(value => ScopeValue) {
                     receiver_scope = ScopeValue::create(scope.data.clone());
                 } else {
-                    println!("Warning > Assignment ignored > Receiver prefix is not a scope > {:?}", &receiver);
-                    self.value = NoneValue::create();
+                    let kind = value.get_type_name();
+                    self.value = self.fail(TYPE_ERROR, &format!("Not a scope > {:?} is a {}", prefix.join("."), kind));
                     return;
                 }
             } else {
-                println!("Warning > Assignment ignored > Could't resolve the receiver > {:?}", &receiver);
-                self.value = NoneValue::create();
+                self.value = self.fail(NAME_ERROR, &format!("Unresolved name > {:?}", prefix.join(".")));
                 return;
             }
         };
 
         self.value = with_value! { self => it.value.accept_simple_visitor(self) };
-        receiver_scope.set_value(&name, self.value.duplicate_or_move());
+
+        if self.is_unwinding() {
+            return;
+        }
+
+        if is_outer {
+            receiver_scope.assign_or_set_value(&name, self.value.duplicate_or_move());
+        } else {
+            receiver_scope.set_value(&name, self.value.duplicate_or_move());
+        }
     }
 
     fn visit_closure_arguments(&mut self, it: &mut ClosureArguments) {
@@ -357,7 +1287,7 @@ impl SimpleVisitor for Runner {
             // let value = with_value! { self => that.accept_simple_visitor(self) };
 
             let receiver = some_or! { extract_text!(that) => {
-                println!("Warning > Assignment ignored > Receiver is not a valid name");
+                self.fail(USAGE_ERROR, "Malformed closure > An argument is not a valid name");
                 return;
             }};
 
@@ -370,6 +1300,10 @@ impl SimpleVisitor for Runner {
 
         for that in &mut it.values {
             last = Some(with_value! { self => that.accept_simple_visitor(self) });
+
+            if self.is_unwinding() {
+                break;
+            }
         }
 
         self.value = if let Some(thing) = last {
@@ -382,23 +1316,8 @@ impl SimpleVisitor for Runner {
     fn visit_closure(&mut self, it: &mut Closure) {
         // don't process closure until it's called
 
-        let arguments = std::mem::replace(
-            &mut it.arguments,
-            Box::new(
-                ClosureArguments {
-                    values: vec![],
-                }
-            )
-        );
-
-        let body = std::mem::replace(
-            &mut it.body,
-            Box::new(
-                Expressions {
-                    values: vec![],
-                }
-            )
-        );
+        let arguments = replicate(&mut it.arguments);
+        let body = replicate(&mut it.body);
 
         self.value = ClosureValue::create(
             Rc::new(
@@ -406,10 +1325,84 @@ impl SimpleVisitor for Runner {
                     ClosureData {
                         arguments: arguments,
                         body: body,
-                        scope: ScopeData::create(Some(self.scope.data.clone())),
+                        scope: self.scope.data.clone(),
                     }
                 )
             )
         );
     }
+
+    fn visit_list(&mut self, it: &mut List) {
+        let mut values = vec![];
+
+        for that in &mut it.values {
+            let value = with_value! { self => that.accept_simple_visitor(self) };
+
+            if self.is_unwinding() {
+                return;
+            }
+
+            values.push(unwrap_provider(value));
+        }
+
+        self.value = ListValue::create(values);
+    }
+
+    fn visit_map(&mut self, it: &mut Map) {
+        let mut entries = vec![];
+
+        for (key, value) in it.keys.iter_mut().zip(it.values.iter_mut()) {
+            let key = with_value! { self => key.accept_simple_visitor(self) };
+
+            if self.is_unwinding() {
+                return;
+            }
+
+            let value = with_value! { self => value.accept_simple_visitor(self) };
+
+            if self.is_unwinding() {
+                return;
+            }
+
+            entries.push((key.to_string(), unwrap_provider(value)));
+        }
+
+        self.value = MapValue::create(entries);
+    }
+
+    fn visit_line_break(&mut self, it: &mut LineBreak) {
+        self.line = it.line;
+        self.value = NoneValue::create();
+    }
+
+    fn visit_background(&mut self, it: &mut Background) {
+        self.run_in_background = true;
+        self.value = with_value! { self => it.value.accept_simple_visitor(self) };
+        self.run_in_background = false;
+    }
+
+    fn visit_subscript(&mut self, it: &mut Subscript) {
+        let target = unwrap_provider(with_value! { self => it.target.accept_simple_visitor(self) });
+
+        if self.is_unwinding() {
+            return;
+        }
+
+        let index = unwrap_provider(with_value! { self => it.index.accept_simple_visitor(self) });
+
+        if self.is_unwinding() {
+            return;
+        }
+
+        let result = target.get(&[index]);
+        let value = self.check(result);
+
+        // just like `$name`, the result is a value
+        // rather than a command name
+        if let Some(..) = cast!(value => ClosureValue) {
+            self.value = value;
+        } else {
+            self.value = ProviderValue::create(value);
+        }
+    }
 }
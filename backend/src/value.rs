@@ -1,17 +1,27 @@
 pub mod none;
 pub mod boolean;
 pub mod number;
+pub mod float;
 pub mod string;
 pub mod closure;
 pub mod scope;
 pub mod provider;
+pub mod status;
+pub mod descriptor;
+pub mod range;
+pub mod error;
+pub mod list;
+pub mod map;
+pub mod pattern;
 
 use std::fmt::Debug;
+use std::cmp::Ordering;
 
 use std::any::Any;
+use std::cell::RefCell;
 
 use boolean::BooleanValue;
-use number::NumberValue;
+use error::{ErrorValue, TYPE_ERROR};
 
 // use helpers::{elvis, some_or};
 
@@ -27,6 +37,7 @@ pub trait Value : Debug {
 
     fn get_type_name(&self) -> &'static str;
     fn to_string(&self) -> String;
+    fn to_boolean(&self) -> bool;
 
     fn get(&self, subscripts: &[Box<dyn Value>]) -> Box<dyn Value>;
     fn set(&self, subscripts: &[Box<dyn Value>], value: Box<dyn Value>) -> Box<dyn Value>;
@@ -47,7 +58,56 @@ pub trait Value : Debug {
     fn contains(&self, other: Box<dyn Value>) -> Box<BooleanValue>;
     fn equals(&self, other: Box<dyn Value>) -> Box<BooleanValue>;
 
-    fn compare(&self, other: Box<dyn Value>) -> Box<NumberValue>;
+    /// `None` if the values can't be ordered.
+    fn compare(&self, other: Box<dyn Value>) -> Option<Ordering>;
+}
+
+/// The result of a binary operator
+/// the operands don't support.
+pub fn mismatch(operator: &str, lefter: &dyn Value, righter: &dyn Value) -> Box<dyn Value> {
+    ErrorValue::create(TYPE_ERROR, &format!(
+        "Unsupported operand types > {} {} {}",
+        lefter.get_type_name(), operator, righter.get_type_name()
+    ))
+}
+
+/// The result of `<`, `>`, `<=` or `>=`,
+/// an error if the operands can't be ordered.
+pub fn compare(operator: &str, lefter: Box<dyn Value>, righter: Box<dyn Value>) -> Box<dyn Value> {
+    let righter_type = righter.get_type_name();
+
+    let ordering = match lefter.compare(righter) {
+        Some(it) => it,
+        None => return ErrorValue::create(TYPE_ERROR, &format!(
+            "Unsupported operand types > {} {} {}",
+            lefter.get_type_name(), operator, righter_type
+        )),
+    };
+
+    BooleanValue::create(match operator {
+        "<" => ordering.is_lt(),
+        ">" => ordering.is_gt(),
+        "<=" => ordering.is_le(),
+        _ => ordering.is_ge(),
+    })
+}
+
+/// The result of a unary operator
+/// the operand doesn't support.
+pub fn unsupported(operator: &str, target: &dyn Value) -> Box<dyn Value> {
+    ErrorValue::create(TYPE_ERROR, &format!(
+        "Unsupported operand type > {}{}",
+        operator, target.get_type_name()
+    ))
+}
+
+/// The result of subscripting a value
+/// that isn't a collection.
+pub fn unsubscriptable(target: &dyn Value) -> Box<dyn Value> {
+    ErrorValue::create(TYPE_ERROR, &format!(
+        "Not subscriptable > {}",
+        target.get_type_name()
+    ))
 }
 
 #[macro_export]
@@ -84,3 +144,25 @@ macro_rules! cast_mut {
 //         self.as_any().downcast_ref::<T>()
 //     }
 // }
+
+thread_local! {
+    /// The lists and maps whose items are
+    /// being walked through at the moment.
+    static VISITED: RefCell<Vec<usize>> = const { RefCell::new(Vec::new()) };
+}
+
+/// Runs the `action` for the container at
+/// the `address`, unless it's already being
+/// walked through, meaning it contains itself.
+/// Then the result is `repeated` instead.
+pub fn guard_cycle<T>(address: usize, repeated: T, action: impl FnOnce() -> T) -> T {
+    if VISITED.with(|it| it.borrow().contains(&address)) {
+        return repeated;
+    }
+
+    VISITED.with(|it| it.borrow_mut().push(address));
+    let result = action();
+    VISITED.with(|it| it.borrow_mut().pop());
+
+    result
+}
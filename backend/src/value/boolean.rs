@@ -1,8 +1,8 @@
 use crate::cast;
 
 use crate::value::*;
-use crate::value::none::NoneValue;
-use crate::value::number::NumberValue;
+
+use std::cmp::Ordering;
 
 pub const BOOLEAN_TYPE: &'static str = "Boolean";
 
@@ -54,48 +54,52 @@ impl Value for BooleanValue {
         }
     }
 
+    fn to_boolean(&self) -> bool {
+        self.value
+    }
+
     fn get(&self, _subscripts: &[Box<dyn Value>]) -> Box<dyn Value> {
-        BooleanValue::create(false)
+        unsubscriptable(self)
     }
 
     fn set(&self, _subscripts: &[Box<dyn Value>], _value: Box<dyn Value>) -> Box<dyn Value> {
-        NoneValue::create()
+        unsubscriptable(self)
     }
 
     fn unary_plus(&self) -> Box<dyn Value> {
-        BooleanValue::create(false)
+        unsupported("+", self)
     }
 
     fn unary_minus(&self) -> Box<dyn Value> {
-        BooleanValue::create(false)
+        unsupported("-", self)
     }
 
     fn not(&self) -> Box<dyn Value> {
-        BooleanValue::create(false)
+        BooleanValue::create(!self.value)
     }
 
-    fn power(&self, _other: Box<dyn Value>) -> Box<dyn Value> {
-        BooleanValue::create(false)
+    fn power(&self, other: Box<dyn Value>) -> Box<dyn Value> {
+        mismatch("^", self, other.as_ref())
     }
 
-    fn times(&self, _other: Box<dyn Value>) -> Box<dyn Value> {
-        BooleanValue::create(false)
+    fn times(&self, other: Box<dyn Value>) -> Box<dyn Value> {
+        mismatch("*", self, other.as_ref())
     }
 
-    fn divide(&self, _other: Box<dyn Value>) -> Box<dyn Value> {
-        BooleanValue::create(false)
+    fn divide(&self, other: Box<dyn Value>) -> Box<dyn Value> {
+        mismatch("/", self, other.as_ref())
     }
 
-    fn reminder(&self, _other: Box<dyn Value>) -> Box<dyn Value> {
-        BooleanValue::create(false)
+    fn reminder(&self, other: Box<dyn Value>) -> Box<dyn Value> {
+        mismatch("%", self, other.as_ref())
     }
 
-    fn plus(&self, _other: Box<dyn Value>) -> Box<dyn Value> {
-        BooleanValue::create(false)
+    fn plus(&self, other: Box<dyn Value>) -> Box<dyn Value> {
+        mismatch("+", self, other.as_ref())
     }
 
-    fn minus(&self, _other: Box<dyn Value>) -> Box<dyn Value> {
-        BooleanValue::create(false)
+    fn minus(&self, other: Box<dyn Value>) -> Box<dyn Value> {
+        mismatch("-", self, other.as_ref())
     }
 
     fn contains(&self, _other: Box<dyn Value>) -> Box<BooleanValue> {
@@ -114,7 +118,7 @@ impl Value for BooleanValue {
         return BooleanValue::create(false);
     }
 
-    fn compare(&self, _other: Box<dyn Value>) -> Box<NumberValue> {
-        NumberValue::create(0)
+    fn compare(&self, _other: Box<dyn Value>) -> Option<Ordering> {
+        None
     }
 }
@@ -1,9 +1,9 @@
 use crate::value::*;
-use crate::value::none::NoneValue;
-use crate::value::number::NumberValue;
 use crate::value::boolean::BooleanValue;
 use crate::value::scope::ScopeData;
 
+use std::cmp::Ordering;
+
 use frontend::ast::*;
 
 use std::rc::Rc;
@@ -90,48 +90,52 @@ impl Value for ClosureValue {
         return "[closure]".to_owned();
     }
 
+    fn to_boolean(&self) -> bool {
+        true
+    }
+
     fn get(&self, _subscripts: &[Box<dyn Value>]) -> Box<dyn Value> {
-        NoneValue::create()
+        unsubscriptable(self)
     }
 
     fn set(&self, _subscripts: &[Box<dyn Value>], _value: Box<dyn Value>) -> Box<dyn Value> {
-        NoneValue::create()
+        unsubscriptable(self)
     }
 
     fn unary_plus(&self) -> Box<dyn Value> {
-        NoneValue::create()
+        unsupported("+", self)
     }
 
     fn unary_minus(&self) -> Box<dyn Value> {
-        NoneValue::create()
+        unsupported("-", self)
     }
 
     fn not(&self) -> Box<dyn Value> {
-        NoneValue::create()
+        BooleanValue::create(false)
     }
 
-    fn power(&self, _other: Box<dyn Value>) -> Box<dyn Value> {
-        NoneValue::create()
+    fn power(&self, other: Box<dyn Value>) -> Box<dyn Value> {
+        mismatch("^", self, other.as_ref())
     }
 
-    fn times(&self, _other: Box<dyn Value>) -> Box<dyn Value> {
-        NoneValue::create()
+    fn times(&self, other: Box<dyn Value>) -> Box<dyn Value> {
+        mismatch("*", self, other.as_ref())
     }
 
-    fn divide(&self, _other: Box<dyn Value>) -> Box<dyn Value> {
-        NoneValue::create()
+    fn divide(&self, other: Box<dyn Value>) -> Box<dyn Value> {
+        mismatch("/", self, other.as_ref())
     }
 
-    fn reminder(&self, _other: Box<dyn Value>) -> Box<dyn Value> {
-        NoneValue::create()
+    fn reminder(&self, other: Box<dyn Value>) -> Box<dyn Value> {
+        mismatch("%", self, other.as_ref())
     }
 
-    fn plus(&self, _other: Box<dyn Value>) -> Box<dyn Value> {
-        NoneValue::create()
+    fn plus(&self, other: Box<dyn Value>) -> Box<dyn Value> {
+        mismatch("+", self, other.as_ref())
     }
 
-    fn minus(&self, _other: Box<dyn Value>) -> Box<dyn Value> {
-        NoneValue::create()
+    fn minus(&self, other: Box<dyn Value>) -> Box<dyn Value> {
+        mismatch("-", self, other.as_ref())
     }
 
     fn contains(&self, _other: Box<dyn Value>) -> Box<BooleanValue> {
@@ -142,7 +146,7 @@ impl Value for ClosureValue {
         BooleanValue::create(false)
     }
 
-    fn compare(&self, _other: Box<dyn Value>) -> Box<NumberValue> {
-        NumberValue::create(0)
+    fn compare(&self, _other: Box<dyn Value>) -> Option<Ordering> {
+        None
     }
 }
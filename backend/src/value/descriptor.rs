@@ -0,0 +1,136 @@
+use crate::cast;
+
+use crate::value::*;
+use crate::value::none::NoneValue;
+use crate::value::boolean::BooleanValue;
+
+use std::cmp::Ordering;
+
+pub const DESCRIPTOR_TYPE: &'static str = "Descriptor";
+
+/// The result of `@target`. It refers to a
+/// file, to a standard stream via `@0`, `@1`,
+/// `@2`, or to the null device via `@null`.
+#[derive(Clone, Debug)]
+pub struct DescriptorValue {
+    pub target: String,
+}
+
+impl DescriptorValue {
+    pub fn new(target: &str) -> DescriptorValue {
+        DescriptorValue {
+            target: target.to_owned(),
+        }
+    }
+
+    pub fn create(target: &str) -> Box<DescriptorValue> {
+        Box::new(DescriptorValue::new(target))
+    }
+
+    /// The number of the standard
+    /// stream, if it refers to one.
+    pub fn get_standard(&self) -> Option<i32> {
+        match &*self.target {
+            "0" => Some(0),
+            "1" => Some(1),
+            "2" => Some(2),
+            _ => None,
+        }
+    }
+
+    pub fn is_null(&self) -> bool {
+        self.target == "null"
+    }
+}
+
+impl Labeled for DescriptorValue {
+    fn get_type_name() -> &'static str {
+        DESCRIPTOR_TYPE
+    }
+}
+
+impl Value for DescriptorValue {
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+
+    fn as_any_mut(&mut self) -> &mut dyn Any {
+        self
+    }
+
+    fn duplicate_or_move(&mut self) -> Box<dyn Value> {
+        DescriptorValue::create(&self.target)
+    }
+
+    fn get_type_name(&self) -> &'static str {
+        DESCRIPTOR_TYPE
+    }
+
+    fn to_string(&self) -> String {
+        "@".to_owned() + &self.target
+    }
+
+    fn to_boolean(&self) -> bool {
+        true
+    }
+
+    fn get(&self, _subscripts: &[Box<dyn Value>]) -> Box<dyn Value> {
+        unsubscriptable(self)
+    }
+
+    fn set(&self, _subscripts: &[Box<dyn Value>], _value: Box<dyn Value>) -> Box<dyn Value> {
+        unsubscriptable(self)
+    }
+
+    fn unary_plus(&self) -> Box<dyn Value> {
+        unsupported("+", self)
+    }
+
+    fn unary_minus(&self) -> Box<dyn Value> {
+        unsupported("-", self)
+    }
+
+    fn not(&self) -> Box<dyn Value> {
+        NoneValue::create()
+    }
+
+    fn power(&self, other: Box<dyn Value>) -> Box<dyn Value> {
+        mismatch("^", self, other.as_ref())
+    }
+
+    fn times(&self, other: Box<dyn Value>) -> Box<dyn Value> {
+        mismatch("*", self, other.as_ref())
+    }
+
+    fn divide(&self, other: Box<dyn Value>) -> Box<dyn Value> {
+        mismatch("/", self, other.as_ref())
+    }
+
+    fn reminder(&self, other: Box<dyn Value>) -> Box<dyn Value> {
+        mismatch("%", self, other.as_ref())
+    }
+
+    fn plus(&self, other: Box<dyn Value>) -> Box<dyn Value> {
+        mismatch("+", self, other.as_ref())
+    }
+
+    fn minus(&self, other: Box<dyn Value>) -> Box<dyn Value> {
+        mismatch("-", self, other.as_ref())
+    }
+
+    fn contains(&self, _other: Box<dyn Value>) -> Box<BooleanValue> {
+        BooleanValue::create(false)
+    }
+
+    fn equals(&self, other: Box<dyn Value>) -> Box<BooleanValue> {
+        if let Some(descriptor) = cast! { other => DescriptorValue } {
+            return BooleanValue::create(self.target == descriptor.target);
+        }
+
+        BooleanValue::create(false)
+    }
+
+    fn compare(&self, _other: Box<dyn Value>) -> Option<Ordering> {
+        None
+    }
+}
@@ -0,0 +1,181 @@
+use crate::cast;
+
+use crate::value::*;
+use crate::value::boolean::BooleanValue;
+
+use std::cmp::Ordering;
+
+pub const ERROR_TYPE: &'static str = "Error";
+
+/// Statements that can't be parsed.
+pub const SYNTAX_ERROR: &'static str = "SyntaxError";
+/// Operands or arguments of a wrong type.
+pub const TYPE_ERROR: &'static str = "TypeError";
+/// Indices out of range.
+pub const INDEX_ERROR: &'static str = "IndexError";
+/// Keys missing from maps.
+pub const KEY_ERROR: &'static str = "KeyError";
+/// Division by zero and overflows.
+pub const ARITHMETIC_ERROR: &'static str = "ArithmeticError";
+/// Names that can't be resolved.
+pub const NAME_ERROR: &'static str = "NameError";
+/// Builtins, assignments and other
+/// constructs used the wrong way.
+pub const USAGE_ERROR: &'static str = "UsageError";
+/// Commands that can't be started.
+pub const COMMAND_ERROR: &'static str = "CommandError";
+/// Files that can't be opened.
+pub const IO_ERROR: &'static str = "IOError";
+/// Globs that match no files.
+pub const GLOB_ERROR: &'static str = "GlobError";
+/// Jobs that don't exist or
+/// can't be signaled.
+pub const JOB_ERROR: &'static str = "JobError";
+/// Statements stopped via Ctrl-C.
+pub const INTERRUPT_ERROR: &'static str = "InterruptError";
+/// Errors raised via `throw`.
+pub const USER_ERROR: &'static str = "Error";
+
+/// A runtime failure. While it's being
+/// raised, it stops the evaluation of the
+/// current statement.
+#[derive(Clone, Debug)]
+pub struct ErrorValue {
+    /// One of the `*_ERROR` constants
+    /// or whatever the user has thrown.
+    pub kind: String,
+    pub message: String,
+    /// The status the failed
+    /// statement gets.
+    pub code: i32,
+    /// The line of the statement that
+    /// has failed, if it's known.
+    pub line: Option<usize>,
+    /// The closures that have been running
+    /// at the moment, the innermost first.
+    pub trace: Vec<String>,
+}
+
+impl ErrorValue {
+    pub fn new(kind: &str, message: &str) -> ErrorValue {
+        ErrorValue {
+            kind: kind.to_owned(),
+            message: message.to_owned(),
+            code: 1,
+            line: None,
+            trace: vec![],
+        }
+    }
+
+    pub fn create(kind: &str, message: &str) -> Box<ErrorValue> {
+        Box::new(ErrorValue::new(kind, message))
+    }
+
+    /// The message followed by the
+    /// location and the call stack.
+    pub fn report(&self) -> String {
+        let mut result = self.kind.clone() + " > " + &self.message;
+
+        if let Some(line) = self.line {
+            result += &format!("\n    at line {}", line);
+        }
+
+        for it in &self.trace {
+            result += &format!("\n    in {}", it);
+        }
+
+        return result;
+    }
+}
+
+impl Labeled for ErrorValue {
+    fn get_type_name() -> &'static str {
+        ERROR_TYPE
+    }
+}
+
+impl Value for ErrorValue {
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+
+    fn as_any_mut(&mut self) -> &mut dyn Any {
+        self
+    }
+
+    fn duplicate_or_move(&mut self) -> Box<dyn Value> {
+        Box::new(self.clone())
+    }
+
+    fn get_type_name(&self) -> &'static str {
+        ERROR_TYPE
+    }
+
+    fn to_string(&self) -> String {
+        self.message.clone()
+    }
+
+    fn to_boolean(&self) -> bool {
+        false
+    }
+
+    fn get(&self, _subscripts: &[Box<dyn Value>]) -> Box<dyn Value> {
+        unsubscriptable(self)
+    }
+
+    fn set(&self, _subscripts: &[Box<dyn Value>], _value: Box<dyn Value>) -> Box<dyn Value> {
+        unsubscriptable(self)
+    }
+
+    fn unary_plus(&self) -> Box<dyn Value> {
+        unsupported("+", self)
+    }
+
+    fn unary_minus(&self) -> Box<dyn Value> {
+        unsupported("-", self)
+    }
+
+    fn not(&self) -> Box<dyn Value> {
+        BooleanValue::create(true)
+    }
+
+    fn power(&self, other: Box<dyn Value>) -> Box<dyn Value> {
+        mismatch("^", self, other.as_ref())
+    }
+
+    fn times(&self, other: Box<dyn Value>) -> Box<dyn Value> {
+        mismatch("*", self, other.as_ref())
+    }
+
+    fn divide(&self, other: Box<dyn Value>) -> Box<dyn Value> {
+        mismatch("/", self, other.as_ref())
+    }
+
+    fn reminder(&self, other: Box<dyn Value>) -> Box<dyn Value> {
+        mismatch("%", self, other.as_ref())
+    }
+
+    fn plus(&self, other: Box<dyn Value>) -> Box<dyn Value> {
+        mismatch("+", self, other.as_ref())
+    }
+
+    fn minus(&self, other: Box<dyn Value>) -> Box<dyn Value> {
+        mismatch("-", self, other.as_ref())
+    }
+
+    fn contains(&self, _other: Box<dyn Value>) -> Box<BooleanValue> {
+        BooleanValue::create(false)
+    }
+
+    fn equals(&self, other: Box<dyn Value>) -> Box<BooleanValue> {
+        if let Some(error) = cast! { other => ErrorValue } {
+            return BooleanValue::create(self.kind == error.kind && self.message == error.message);
+        }
+
+        BooleanValue::create(false)
+    }
+
+    fn compare(&self, _other: Box<dyn Value>) -> Option<Ordering> {
+        None
+    }
+}
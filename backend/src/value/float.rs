@@ -0,0 +1,183 @@
+use crate::cast;
+
+use crate::value::*;
+use crate::value::boolean::BooleanValue;
+use crate::value::number::{NumberValue, Numeric, to_numeric};
+use crate::value::error::{ErrorValue, ARITHMETIC_ERROR};
+
+use std::cmp::Ordering;
+
+pub const FLOAT_TYPE: &'static str = "Float";
+
+/// The result of `1.5`, `2e10` or
+/// an inexact division.
+#[derive(Clone, Debug)]
+pub struct FloatValue {
+    pub value: f64,
+}
+
+impl FloatValue {
+    pub fn new(value: f64) -> FloatValue {
+        FloatValue {
+            value: value,
+        }
+    }
+
+    pub fn create(initial: f64) -> Box<FloatValue> {
+        Box::new(FloatValue::new(initial))
+    }
+}
+
+/// Arithmetics accepts both floats
+/// and integers, but not strings.
+fn to_operand(value: &dyn Value) -> Option<f64> {
+    if let Some(float) = cast! { value => FloatValue } {
+        return Some(float.value);
+    }
+
+    if let Some(number) = cast! { value => NumberValue } {
+        return Some(number.to_float());
+    }
+
+    return None;
+}
+
+impl Labeled for FloatValue {
+    fn get_type_name() -> &'static str {
+        FLOAT_TYPE
+    }
+}
+
+impl Value for FloatValue {
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+
+    fn as_any_mut(&mut self) -> &mut dyn Any {
+        self
+    }
+
+    fn duplicate_or_move(&mut self) -> Box<dyn Value> {
+        FloatValue::create(self.value)
+    }
+
+    fn get_type_name(&self) -> &'static str {
+        FLOAT_TYPE
+    }
+
+    /// Always has a dot or an exponent,
+    /// so `2.0` doesn't look like `2`.
+    fn to_string(&self) -> String {
+        return format!("{:?}", self.value);
+    }
+
+    fn to_boolean(&self) -> bool {
+        self.value != 0.0
+    }
+
+    fn get(&self, _subscripts: &[Box<dyn Value>]) -> Box<dyn Value> {
+        unsubscriptable(self)
+    }
+
+    fn set(&self, _subscripts: &[Box<dyn Value>], _value: Box<dyn Value>) -> Box<dyn Value> {
+        unsubscriptable(self)
+    }
+
+    fn unary_plus(&self) -> Box<dyn Value> {
+        FloatValue::create(
+            self.value
+        )
+    }
+
+    fn unary_minus(&self) -> Box<dyn Value> {
+        FloatValue::create(
+            -self.value
+        )
+    }
+
+    fn not(&self) -> Box<dyn Value> {
+        BooleanValue::create(self.value == 0.0)
+    }
+
+    fn power(&self, other: Box<dyn Value>) -> Box<dyn Value> {
+        if let Some(number) = to_operand(other.as_ref()) {
+            if self.value == 0.0 && number < 0.0 {
+                return ErrorValue::create(ARITHMETIC_ERROR, "Division by zero");
+            }
+
+            return FloatValue::create(self.value.powf(number));
+        }
+
+        mismatch("^", self, other.as_ref())
+    }
+
+    fn times(&self, other: Box<dyn Value>) -> Box<dyn Value> {
+        if let Some(number) = to_operand(other.as_ref()) {
+            return FloatValue::create(self.value * number);
+        }
+
+        mismatch("*", self, other.as_ref())
+    }
+
+    fn divide(&self, other: Box<dyn Value>) -> Box<dyn Value> {
+        if let Some(number) = to_operand(other.as_ref()) {
+            if number == 0.0 {
+                return ErrorValue::create(ARITHMETIC_ERROR, "Division by zero");
+            }
+
+            return FloatValue::create(self.value / number);
+        }
+
+        mismatch("/", self, other.as_ref())
+    }
+
+    /// The result has the sign
+    /// of the dividend.
+    fn reminder(&self, other: Box<dyn Value>) -> Box<dyn Value> {
+        if let Some(number) = to_operand(other.as_ref()) {
+            if number == 0.0 {
+                return ErrorValue::create(ARITHMETIC_ERROR, "Division by zero");
+            }
+
+            return FloatValue::create(self.value % number);
+        }
+
+        mismatch("%", self, other.as_ref())
+    }
+
+    fn plus(&self, other: Box<dyn Value>) -> Box<dyn Value> {
+        if let Some(number) = to_operand(other.as_ref()) {
+            return FloatValue::create(self.value + number);
+        }
+
+        mismatch("+", self, other.as_ref())
+    }
+
+    fn minus(&self, other: Box<dyn Value>) -> Box<dyn Value> {
+        if let Some(number) = to_operand(other.as_ref()) {
+            return FloatValue::create(self.value - number);
+        }
+
+        mismatch("-", self, other.as_ref())
+    }
+
+    fn contains(&self, _other: Box<dyn Value>) -> Box<BooleanValue> {
+        BooleanValue::create(false)
+    }
+
+    fn equals(&self, other: Box<dyn Value>) -> Box<BooleanValue> {
+        match to_numeric(other.as_ref()) {
+            Some(Numeric::Integer(it)) => BooleanValue::create(self.value == NumberValue::new(it).to_float()),
+            Some(Numeric::Float(it)) => BooleanValue::create(self.value == it),
+            None => BooleanValue::create(false),
+        }
+    }
+
+    fn compare(&self, other: Box<dyn Value>) -> Option<Ordering> {
+        match to_numeric(other.as_ref()) {
+            Some(Numeric::Integer(it)) => self.value.partial_cmp(&NumberValue::new(it).to_float()),
+            Some(Numeric::Float(it)) => self.value.partial_cmp(&it),
+            None => None,
+        }
+    }
+}
@@ -0,0 +1,220 @@
+use crate::cast;
+
+use crate::value::*;
+use crate::value::boolean::BooleanValue;
+use crate::value::number::NumberValue;
+use crate::value::error::{ErrorValue, TYPE_ERROR, INDEX_ERROR};
+
+use helpers::{elvis, some_or};
+
+use std::rc::Rc;
+use std::cell::RefCell;
+use std::cmp::Ordering;
+
+pub const LIST_TYPE: &'static str = "List";
+
+/// The result of `[a, b, c]`. Like scopes,
+/// lists are shared rather than copied, so
+/// `set()` is seen via all the variables
+/// that hold the list.
+#[derive(Clone)]
+pub struct ListValue {
+    pub values: Rc<RefCell<Vec<Box<dyn Value>>>>,
+}
+
+impl ListValue {
+    pub fn new(values: Vec<Box<dyn Value>>) -> ListValue {
+        ListValue {
+            values: Rc::new(RefCell::new(values)),
+        }
+    }
+
+    pub fn create(values: Vec<Box<dyn Value>>) -> Box<ListValue> {
+        Box::new(ListValue::new(values))
+    }
+
+    fn get_address(&self) -> usize {
+        Rc::as_ptr(&self.values) as *const u8 as usize
+    }
+
+    /// Copies the items, so that they
+    /// can be iterated over while the list
+    /// is being changed.
+    pub fn items(&self) -> Vec<Box<dyn Value>> {
+        self.values.borrow_mut()
+            .iter_mut()
+            .map(|it| it.duplicate_or_move())
+            .collect()
+    }
+
+    /// Finds the position the subscript refers to.
+    /// Negative indices are counted from the end.
+    fn locate(&self, subscripts: &[Box<dyn Value>]) -> Result<usize, Box<dyn Value>> {
+        if subscripts.len() != 1 {
+            return Err(ErrorValue::create(TYPE_ERROR, "Lists expect a single index"));
+        }
+
+        let number = some_or! { cast!(subscripts[0] => NumberValue) => {
+            return Err(ErrorValue::create(TYPE_ERROR, &format!(
+                "The index is not a number > {:?}", subscripts[0].to_string()
+            )));
+        }};
+
+        let length = self.values.borrow().len() as i32;
+        let value = some_or! { number.to_i32() => i32::MAX };
+        let index = if value < 0 {
+            value + length
+        } else {
+            value
+        };
+
+        if index < 0 || index >= length {
+            return Err(ErrorValue::create(INDEX_ERROR, &format!(
+                "The index is out of range > {} of {}", number.value, length
+            )));
+        }
+
+        Ok(index as usize)
+    }
+}
+
+impl Debug for ListValue {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("ListValue")
+            .field("values", &self.to_string())
+            .finish()
+    }
+}
+
+impl Labeled for ListValue {
+    fn get_type_name() -> &'static str {
+        LIST_TYPE
+    }
+}
+
+impl Value for ListValue {
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+
+    fn as_any_mut(&mut self) -> &mut dyn Any {
+        self
+    }
+
+    fn duplicate_or_move(&mut self) -> Box<dyn Value> {
+        Box::new(self.clone())
+    }
+
+    fn get_type_name(&self) -> &'static str {
+        LIST_TYPE
+    }
+
+    fn to_string(&self) -> String {
+        guard_cycle(self.get_address(), "[...]".to_owned(), || {
+            let items = self.values.borrow()
+                .iter()
+                .map(|it| it.to_string())
+                .collect::<Vec<String>>();
+
+            "[".to_owned() + &items.join(", ") + "]"
+        })
+    }
+
+    fn to_boolean(&self) -> bool {
+        !self.values.borrow().is_empty()
+    }
+
+    fn get(&self, subscripts: &[Box<dyn Value>]) -> Box<dyn Value> {
+        match self.locate(subscripts) {
+            Ok(index) => self.values.borrow_mut()[index].duplicate_or_move(),
+            Err(error) => error,
+        }
+    }
+
+    fn set(&self, subscripts: &[Box<dyn Value>], mut value: Box<dyn Value>) -> Box<dyn Value> {
+        match self.locate(subscripts) {
+            Ok(index) => {
+                self.values.borrow_mut()[index] = value.duplicate_or_move();
+                value
+            }
+            Err(error) => error,
+        }
+    }
+
+    fn unary_plus(&self) -> Box<dyn Value> {
+        unsupported("+", self)
+    }
+
+    fn unary_minus(&self) -> Box<dyn Value> {
+        unsupported("-", self)
+    }
+
+    fn not(&self) -> Box<dyn Value> {
+        BooleanValue::create(!self.to_boolean())
+    }
+
+    fn power(&self, other: Box<dyn Value>) -> Box<dyn Value> {
+        mismatch("^", self, other.as_ref())
+    }
+
+    fn times(&self, other: Box<dyn Value>) -> Box<dyn Value> {
+        mismatch("*", self, other.as_ref())
+    }
+
+    fn divide(&self, other: Box<dyn Value>) -> Box<dyn Value> {
+        mismatch("/", self, other.as_ref())
+    }
+
+    fn reminder(&self, other: Box<dyn Value>) -> Box<dyn Value> {
+        mismatch("%", self, other.as_ref())
+    }
+
+    fn plus(&self, other: Box<dyn Value>) -> Box<dyn Value> {
+        if let Some(list) = cast! { other => ListValue } {
+            let mut items = self.items();
+            items.extend(list.items());
+            return ListValue::create(items);
+        }
+
+        mismatch("+", self, other.as_ref())
+    }
+
+    fn minus(&self, other: Box<dyn Value>) -> Box<dyn Value> {
+        mismatch("-", self, other.as_ref())
+    }
+
+    fn contains(&self, mut other: Box<dyn Value>) -> Box<BooleanValue> {
+        let has = self.items()
+            .iter()
+            .any(|it| it.equals(other.duplicate_or_move()).value);
+
+        BooleanValue::create(has)
+    }
+
+    fn equals(&self, other: Box<dyn Value>) -> Box<BooleanValue> {
+        if let Some(list) = cast! { other => ListValue } {
+            let items = self.items();
+            let mut others = list.items();
+
+            if others.len() != items.len() {
+                return BooleanValue::create(false);
+            }
+
+            // a list that contains itself is
+            // as equal as the rest of it is
+            let same = guard_cycle(self.get_address(), true, || {
+                items.iter()
+                    .zip(others.iter_mut())
+                    .all(|(it, that)| it.equals(that.duplicate_or_move()).value)
+            });
+
+            return BooleanValue::create(same);
+        }
+
+        BooleanValue::create(false)
+    }
+
+    fn compare(&self, _other: Box<dyn Value>) -> Option<Ordering> {
+        None
+    }
+}
@@ -0,0 +1,241 @@
+use crate::cast;
+
+use crate::value::*;
+use crate::value::boolean::BooleanValue;
+use crate::value::error::{ErrorValue, TYPE_ERROR, KEY_ERROR};
+
+use std::rc::Rc;
+use std::cell::RefCell;
+use std::cmp::Ordering;
+
+pub const MAP_TYPE: &'static str = "Map";
+
+/// A key and its value.
+pub type Entry = (String, Box<dyn Value>);
+
+/// The result of `[key: value, ...]`. The keys
+/// are strings and keep the order they've been
+/// added in. Like lists, maps are shared rather
+/// than copied.
+#[derive(Clone)]
+pub struct MapValue {
+    pub entries: Rc<RefCell<Vec<Entry>>>,
+}
+
+impl MapValue {
+    pub fn new(entries: Vec<Entry>) -> MapValue {
+        let map = MapValue {
+            entries: Rc::new(RefCell::new(vec![])),
+        };
+
+        for (key, value) in entries {
+            map.insert(&key, value);
+        }
+
+        map
+    }
+
+    pub fn create(entries: Vec<Entry>) -> Box<MapValue> {
+        Box::new(MapValue::new(entries))
+    }
+
+    fn get_address(&self) -> usize {
+        Rc::as_ptr(&self.entries) as *const u8 as usize
+    }
+
+    pub fn keys(&self) -> Vec<String> {
+        self.entries.borrow()
+            .iter()
+            .map(|(key, _)| key.clone())
+            .collect()
+    }
+
+    pub fn get_value(&self, key: &str) -> Option<Box<dyn Value>> {
+        self.entries.borrow_mut()
+            .iter_mut()
+            .find(|(it, _)| it == key)
+            .map(|(_, value)| value.duplicate_or_move())
+    }
+
+    /// Replaces the value if the key
+    /// is there, or adds a new entry.
+    pub fn insert(&self, key: &str, value: Box<dyn Value>) {
+        let mut entries = self.entries.borrow_mut();
+
+        if let Some(entry) = entries.iter_mut().find(|(it, _)| it == key) {
+            entry.1 = value;
+        } else {
+            entries.push((key.to_owned(), value));
+        }
+    }
+
+    /// Copies the entries, so that they
+    /// can be used while the map is being
+    /// changed.
+    pub fn items(&self) -> Vec<Entry> {
+        self.entries.borrow_mut()
+            .iter_mut()
+            .map(|(key, value)| (key.clone(), value.duplicate_or_move()))
+            .collect()
+    }
+}
+
+fn to_key(subscripts: &[Box<dyn Value>]) -> Result<String, Box<dyn Value>> {
+    if subscripts.len() != 1 {
+        return Err(ErrorValue::create(TYPE_ERROR, "Maps expect a single key"));
+    }
+
+    Ok(subscripts[0].to_string())
+}
+
+impl Debug for MapValue {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("MapValue")
+            .field("entries", &self.to_string())
+            .finish()
+    }
+}
+
+impl Labeled for MapValue {
+    fn get_type_name() -> &'static str {
+        MAP_TYPE
+    }
+}
+
+impl Value for MapValue {
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+
+    fn as_any_mut(&mut self) -> &mut dyn Any {
+        self
+    }
+
+    fn duplicate_or_move(&mut self) -> Box<dyn Value> {
+        Box::new(self.clone())
+    }
+
+    fn get_type_name(&self) -> &'static str {
+        MAP_TYPE
+    }
+
+    fn to_string(&self) -> String {
+        let entries = self.entries.borrow();
+
+        if entries.is_empty() {
+            return "[:]".to_owned();
+        }
+
+        guard_cycle(self.get_address(), "[...]".to_owned(), || {
+            let items = entries.iter()
+                .map(|(key, value)| key.clone() + ": " + &value.to_string())
+                .collect::<Vec<String>>();
+
+            "[".to_owned() + &items.join(", ") + "]"
+        })
+    }
+
+    fn to_boolean(&self) -> bool {
+        !self.entries.borrow().is_empty()
+    }
+
+    fn get(&self, subscripts: &[Box<dyn Value>]) -> Box<dyn Value> {
+        let key = match to_key(subscripts) {
+            Ok(it) => it,
+            Err(error) => return error,
+        };
+
+        match self.get_value(&key) {
+            Some(it) => it,
+            None => ErrorValue::create(KEY_ERROR, &format!("No such key > {:?}", &key)),
+        }
+    }
+
+    fn set(&self, subscripts: &[Box<dyn Value>], mut value: Box<dyn Value>) -> Box<dyn Value> {
+        let key = match to_key(subscripts) {
+            Ok(it) => it,
+            Err(error) => return error,
+        };
+
+        self.insert(&key, value.duplicate_or_move());
+        value
+    }
+
+    fn unary_plus(&self) -> Box<dyn Value> {
+        unsupported("+", self)
+    }
+
+    fn unary_minus(&self) -> Box<dyn Value> {
+        unsupported("-", self)
+    }
+
+    fn not(&self) -> Box<dyn Value> {
+        BooleanValue::create(!self.to_boolean())
+    }
+
+    fn power(&self, other: Box<dyn Value>) -> Box<dyn Value> {
+        mismatch("^", self, other.as_ref())
+    }
+
+    fn times(&self, other: Box<dyn Value>) -> Box<dyn Value> {
+        mismatch("*", self, other.as_ref())
+    }
+
+    fn divide(&self, other: Box<dyn Value>) -> Box<dyn Value> {
+        mismatch("/", self, other.as_ref())
+    }
+
+    fn reminder(&self, other: Box<dyn Value>) -> Box<dyn Value> {
+        mismatch("%", self, other.as_ref())
+    }
+
+    /// A new map with the entries of both,
+    /// the righter ones win.
+    fn plus(&self, other: Box<dyn Value>) -> Box<dyn Value> {
+        if let Some(map) = cast! { other => MapValue } {
+            let mut entries = self.items();
+            entries.extend(map.items());
+            return MapValue::create(entries);
+        }
+
+        mismatch("+", self, other.as_ref())
+    }
+
+    fn minus(&self, other: Box<dyn Value>) -> Box<dyn Value> {
+        mismatch("-", self, other.as_ref())
+    }
+
+    fn contains(&self, other: Box<dyn Value>) -> Box<BooleanValue> {
+        let key = other.to_string();
+        let has = self.entries.borrow().iter().any(|(it, _)| *it == key);
+        BooleanValue::create(has)
+    }
+
+    fn equals(&self, other: Box<dyn Value>) -> Box<BooleanValue> {
+        if let Some(map) = cast! { other => MapValue } {
+            let items = self.items();
+
+            if items.len() != map.entries.borrow().len() {
+                return BooleanValue::create(false);
+            }
+
+            let same = guard_cycle(self.get_address(), true, || {
+                items.into_iter().all(|(key, value)| {
+                    match map.get_value(&key) {
+                        Some(that) => value.equals(that).value,
+                        None => false,
+                    }
+                })
+            });
+
+            return BooleanValue::create(same);
+        }
+
+        BooleanValue::create(false)
+    }
+
+    fn compare(&self, _other: Box<dyn Value>) -> Option<Ordering> {
+        None
+    }
+}
+
@@ -1,26 +1,111 @@
 use crate::cast;
 
 use crate::value::*;
-use crate::value::none::NoneValue;
 use crate::value::boolean::BooleanValue;
+use crate::value::float::FloatValue;
+use crate::value::string::StringValue;
+use crate::value::status::StatusValue;
+use crate::value::error::{ErrorValue, ARITHMETIC_ERROR};
+
+use helpers::{elvis, some_or};
+
+use num_bigint::BigInt;
+use num_traits::{Zero, One, Signed, ToPrimitive};
+
+use std::cmp::Ordering;
 
 pub const NUMBER_TYPE: &'static str = "Number";
 
+/// The largest result `^` may produce.
+/// Keeps `10 ^ 1000000000` from eating
+/// all the memory.
+const MAX_POWER_BITS: u64 = 1 << 20;
+
+/// An integer of any size, so it
+/// never overflows.
 #[derive(Clone, Debug)]
 pub struct NumberValue {
-    pub value: i32,
+    pub value: BigInt,
 }
 
 impl NumberValue {
-    pub fn new(value: i32) -> NumberValue {
+    pub fn new(value: BigInt) -> NumberValue {
         NumberValue {
             value: value,
         }
     }
 
     pub fn create(initial: i32) -> Box<NumberValue> {
+        Box::new(NumberValue::new(BigInt::from(initial)))
+    }
+
+    pub fn create_big(initial: BigInt) -> Box<NumberValue> {
         Box::new(NumberValue::new(initial))
     }
+
+    /// None if the number
+    /// doesn't fit.
+    pub fn to_i32(&self) -> Option<i32> {
+        self.value.to_i32()
+    }
+
+    /// The nearest float, the huge
+    /// numbers become infinities.
+    pub fn to_float(&self) -> f64 {
+        self.value.to_f64().unwrap_or(f64::NAN)
+    }
+}
+
+/// A number of either kind.
+pub enum Numeric {
+    Integer(BigInt),
+    Float(f64),
+}
+
+impl Numeric {
+    pub fn to_value(self) -> Box<dyn Value> {
+        match self {
+            Numeric::Integer(it) => NumberValue::create_big(it),
+            Numeric::Float(it) => FloatValue::create(it),
+        }
+    }
+}
+
+/// Numbers can be compared with the
+/// strings that hold numbers, like the
+/// output of `wc -l`, and with statuses.
+pub fn to_numeric(value: &dyn Value) -> Option<Numeric> {
+    if let Some(number) = cast! { value => NumberValue } {
+        return Some(Numeric::Integer(number.value.clone()));
+    }
+
+    if let Some(float) = cast! { value => FloatValue } {
+        return Some(Numeric::Float(float.value));
+    }
+
+    if let Some(status) = cast! { value => StatusValue } {
+        return Some(Numeric::Integer(BigInt::from(status.code)));
+    }
+
+    if let Some(string) = cast! { value => StringValue } {
+        let text = string.value.trim();
+
+        if let Ok(it) = text.parse::<BigInt>() {
+            return Some(Numeric::Integer(it));
+        }
+
+        if let Ok(it) = text.parse::<f64>() {
+            return Some(Numeric::Float(it));
+        }
+    }
+
+    return None;
+}
+
+/// Integer operations turn into float
+/// ones if the other operand is a float.
+fn to_float_value(number: &NumberValue) -> FloatValue {
+    FloatValue::new(number.to_float())
 }
 
 impl Labeled for NumberValue {
@@ -39,7 +124,7 @@ impl Value for NumberValue {
     }
 
     fn duplicate_or_move(&mut self) -> Box<dyn Value> {
-        NumberValue::create(self.value)
+        NumberValue::create_big(self.value.clone())
     }
 
     fn get_type_name(&self) -> &'static str {
@@ -50,104 +135,152 @@ impl Value for NumberValue {
         return self.value.to_string();
     }
 
+    fn to_boolean(&self) -> bool {
+        !self.value.is_zero()
+    }
+
     fn get(&self, _subscripts: &[Box<dyn Value>]) -> Box<dyn Value> {
-        NoneValue::create()
+        unsubscriptable(self)
     }
 
     fn set(&self, _subscripts: &[Box<dyn Value>], _value: Box<dyn Value>) -> Box<dyn Value> {
-        NoneValue::create()
+        unsubscriptable(self)
     }
 
     fn unary_plus(&self) -> Box<dyn Value> {
-        NumberValue::create(
-            self.value
+        NumberValue::create_big(
+            self.value.clone()
         )
     }
 
     fn unary_minus(&self) -> Box<dyn Value> {
-        NumberValue::create(
-            -self.value
+        NumberValue::create_big(
+            -&self.value
         )
     }
 
     fn not(&self) -> Box<dyn Value> {
-        NumberValue::create(
-            !self.value
-        )
+        BooleanValue::create(self.value.is_zero())
     }
 
+    /// Negative exponents give floats.
     fn power(&self, other: Box<dyn Value>) -> Box<dyn Value> {
         let maybe_number = cast! { other => NumberValue };
 
         if let Some(number) = maybe_number {
-            let mut result = self.value;
+            if number.value.is_negative() {
+                if self.value.is_zero() {
+                    return ErrorValue::create(ARITHMETIC_ERROR, "Division by zero");
+                }
 
-            if number.value < 0 {
-                return NumberValue::create(0);
+                return to_float_value(self).power(other);
             }
 
-            if number.value == 0 {
-                return NumberValue::create(1);
-            }
+            let exponent = some_or! { number.value.to_u32() => {
+                return ErrorValue::create(ARITHMETIC_ERROR, "The exponent is too large");
+            }};
+
+            let trivial = self.value.abs() <= BigInt::one();
 
-            for _ in 1..number.value {
-                result *= self.value;
+            if !trivial && self.value.bits() * exponent as u64 > MAX_POWER_BITS {
+                return ErrorValue::create(ARITHMETIC_ERROR, "The result is too large");
             }
 
-            return NumberValue::create(result);
+            return NumberValue::create_big(self.value.pow(exponent));
         }
 
-        NoneValue::create()
+        if let Some(..) = cast! { other => FloatValue } {
+            return to_float_value(self).power(other);
+        }
+
+        mismatch("^", self, other.as_ref())
     }
 
     fn times(&self, other: Box<dyn Value>) -> Box<dyn Value> {
         let maybe_number = cast! { other => NumberValue };
 
         if let Some(number) = maybe_number {
-            return NumberValue::create(self.value * number.value);
+            return NumberValue::create_big(&self.value * &number.value);
+        }
+
+        if let Some(..) = cast! { other => FloatValue } {
+            return to_float_value(self).times(other);
         }
 
-        NoneValue::create()
+        mismatch("*", self, other.as_ref())
     }
 
+    /// Stays an integer only if the
+    /// division is exact, so `6 / 3` is
+    /// `2`, but `5 / 2` is `2.5`.
     fn divide(&self, other: Box<dyn Value>) -> Box<dyn Value> {
         let maybe_number = cast! { other => NumberValue };
 
         if let Some(number) = maybe_number {
-            return NumberValue::create(self.value / number.value);
+            if number.value.is_zero() {
+                return ErrorValue::create(ARITHMETIC_ERROR, "Division by zero");
+            }
+
+            if (&self.value % &number.value).is_zero() {
+                return NumberValue::create_big(&self.value / &number.value);
+            }
+
+            return FloatValue::create(self.to_float() / number.to_float());
         }
 
-        NoneValue::create()
+        if let Some(..) = cast! { other => FloatValue } {
+            return to_float_value(self).divide(other);
+        }
+
+        mismatch("/", self, other.as_ref())
     }
 
+    /// The result has the sign
+    /// of the dividend.
     fn reminder(&self, other: Box<dyn Value>) -> Box<dyn Value> {
         let maybe_number = cast! { other => NumberValue };
 
         if let Some(number) = maybe_number {
-            return NumberValue::create(self.value % number.value);
+            if number.value.is_zero() {
+                return ErrorValue::create(ARITHMETIC_ERROR, "Division by zero");
+            }
+
+            return NumberValue::create_big(&self.value % &number.value);
         }
 
-        NoneValue::create()
+        if let Some(..) = cast! { other => FloatValue } {
+            return to_float_value(self).reminder(other);
+        }
+
+        mismatch("%", self, other.as_ref())
     }
 
     fn plus(&self, other: Box<dyn Value>) -> Box<dyn Value> {
         let maybe_number = cast! { other => NumberValue };
 
         if let Some(number) = maybe_number {
-            return NumberValue::create(self.value + number.value);
+            return NumberValue::create_big(&self.value + &number.value);
+        }
+
+        if let Some(..) = cast! { other => FloatValue } {
+            return to_float_value(self).plus(other);
         }
 
-        NoneValue::create()
+        mismatch("+", self, other.as_ref())
     }
 
     fn minus(&self, other: Box<dyn Value>) -> Box<dyn Value> {
         let maybe_number = cast! { other => NumberValue };
 
         if let Some(number) = maybe_number {
-            return NumberValue::create(self.value - number.value);
+            return NumberValue::create_big(&self.value - &number.value);
         }
 
-        NoneValue::create()
+        if let Some(..) = cast! { other => FloatValue } {
+            return to_float_value(self).minus(other);
+        }
+
+        mismatch("-", self, other.as_ref())
     }
 
     fn contains(&self, _other: Box<dyn Value>) -> Box<BooleanValue> {
@@ -155,28 +288,18 @@ impl Value for NumberValue {
     }
 
     fn equals(&self, other: Box<dyn Value>) -> Box<BooleanValue> {
-        let maybe_number = cast! { other => NumberValue };
-
-        if let Some(number) = maybe_number {
-            return BooleanValue::create(self.value == number.value);
+        match to_numeric(other.as_ref()) {
+            Some(Numeric::Integer(it)) => BooleanValue::create(self.value == it),
+            Some(Numeric::Float(it)) => BooleanValue::create(self.to_float() == it),
+            None => BooleanValue::create(false),
         }
-
-        BooleanValue::create(false)
     }
 
-    fn compare(&self, other: Box<dyn Value>) -> Box<NumberValue> {
-        let maybe_number = cast! { other => NumberValue };
-
-        if let Some(number) = maybe_number {
-            let that = match self.value - number.value {
-                0 => 0,
-                it if it > 0 => 1,
-                _ => -1,
-            };
-
-            return NumberValue::create(that);
+    fn compare(&self, other: Box<dyn Value>) -> Option<Ordering> {
+        match to_numeric(other.as_ref()) {
+            Some(Numeric::Integer(it)) => Some(self.value.cmp(&it)),
+            Some(Numeric::Float(it)) => self.to_float().partial_cmp(&it),
+            None => None,
         }
-
-        NumberValue::create(0)
     }
 }
@@ -0,0 +1,366 @@
+use crate::value::*;
+use crate::value::boolean::BooleanValue;
+use crate::value::string::StringValue;
+
+use std::cmp::Ordering;
+
+use frontend::lexer::{unescape_word, EXPANDED_SYMBOLS};
+
+use processing::users::find_home_directory;
+
+use glob::{glob_with, MatchOptions};
+
+pub const PATTERN_TYPE: &'static str = "Pattern";
+
+/// What a glob that matches
+/// no files turns into.
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub enum Unmatched {
+    /// Fails the command.
+    Error,
+    /// Passes the glob as it's written.
+    Keep,
+    /// Drops the argument.
+    Empty,
+}
+
+impl Unmatched {
+    pub fn from_name(name: &str) -> Option<Unmatched> {
+        match name {
+            "error" => Some(Unmatched::Error),
+            "keep" => Some(Unmatched::Keep),
+            "empty" => Some(Unmatched::Empty),
+            _ => None,
+        }
+    }
+}
+
+/// The result of an unquoted word like `*.rs`,
+/// `file?.txt`, `[a-c]*` or `{src,tests}/**/*.rs`.
+/// It's only matched against the files when
+/// passed to an external command, elsewhere
+/// it's the string it's written as.
+#[derive(Clone, Debug)]
+pub struct PatternValue {
+    /// Keeps the backslashes before the
+    /// escaped glob symbols.
+    pub pattern: String,
+}
+
+impl PatternValue {
+    pub fn new(pattern: &str) -> PatternValue {
+        PatternValue {
+            pattern: pattern.to_owned(),
+        }
+    }
+
+    pub fn create(pattern: &str) -> Box<PatternValue> {
+        Box::new(PatternValue::new(pattern))
+    }
+
+    /// The paths the pattern matches, sorted,
+    /// or `Err` with the alternative that has
+    /// matched nothing.
+    pub fn expand(&self, unmatched: Unmatched) -> Result<Vec<String>, String> {
+        let mut result = vec![];
+
+        for alternative in expand_braces(&self.pattern) {
+            if !has_wildcards(&alternative) {
+                result.push(unescape_word(&alternative));
+                continue;
+            }
+
+            let matches = find_matches(&alternative);
+
+            if !matches.is_empty() {
+                result.extend(matches);
+                continue;
+            }
+
+            match unmatched {
+                Unmatched::Error => return Err(unescape_word(&alternative)),
+                Unmatched::Keep => result.push(unescape_word(&alternative)),
+                Unmatched::Empty => {},
+            }
+        }
+
+        Ok(result)
+    }
+
+    fn to_string_value(&self) -> Box<dyn Value> {
+        StringValue::create(&self.to_string())
+    }
+}
+
+/// Finds the position of the unescaped
+/// `{` and its `}` as well as the commas
+/// between them.
+fn find_alternation(pattern: &[char]) -> Option<(usize, Vec<usize>, usize)> {
+    let mut index = 0;
+
+    while index < pattern.len() {
+        match pattern[index] {
+            '\\' => index += 1,
+            '{' => {
+                if let Some((commas, end)) = find_commas(pattern, index) {
+                    if !commas.is_empty() {
+                        return Some((index, commas, end));
+                    }
+                }
+            },
+            _ => {},
+        }
+
+        index += 1;
+    }
+
+    None
+}
+
+fn find_commas(pattern: &[char], start: usize) -> Option<(Vec<usize>, usize)> {
+    let mut commas = vec![];
+    let mut depth = 0;
+    let mut index = start + 1;
+
+    while index < pattern.len() {
+        match pattern[index] {
+            '\\' => index += 1,
+            '{' => depth += 1,
+            '}' if depth == 0 => return Some((commas, index)),
+            '}' => depth -= 1,
+            ',' if depth == 0 => commas.push(index),
+            _ => {},
+        }
+
+        index += 1;
+    }
+
+    None
+}
+
+/// `a{b,c}d` gives `abd` and `acd`. Braces
+/// without commas are left as they are.
+fn expand_braces(pattern: &str) -> Vec<String> {
+    let symbols = pattern.chars().collect::<Vec<char>>();
+
+    let (start, commas, end) = match find_alternation(&symbols) {
+        Some(it) => it,
+        None => return vec![pattern.to_owned()],
+    };
+
+    let prefix = symbols[..start].iter().collect::<String>();
+    let suffix = symbols[end + 1..].iter().collect::<String>();
+
+    let mut bounds = vec![start];
+    bounds.extend(commas);
+    bounds.push(end);
+
+    let mut result = vec![];
+
+    for it in bounds.windows(2) {
+        let middle = symbols[it[0] + 1..it[1]].iter().collect::<String>();
+        result.extend(expand_braces(&(prefix.clone() + &middle + &suffix)));
+    }
+
+    result
+}
+
+fn has_wildcards(pattern: &str) -> bool {
+    let mut symbols = pattern.chars();
+
+    while let Some(symbol) = symbols.next() {
+        match symbol {
+            '\\' => { symbols.next(); },
+            '*' | '?' | '[' => return true,
+            _ => {},
+        }
+    }
+
+    false
+}
+
+/// `glob` escapes symbols with `[]`
+/// rather than with backslashes.
+fn to_glob_syntax(pattern: &str) -> String {
+    let mut result = String::new();
+    let mut symbols = pattern.chars().peekable();
+
+    while let Some(symbol) = symbols.next() {
+        match symbols.peek() {
+            Some(&next) if symbol == '\\' && EXPANDED_SYMBOLS.contains(next) => {
+                result += &glob::Pattern::escape(&next.to_string());
+                symbols.next();
+            },
+            _ => result.push(symbol),
+        }
+    }
+
+    result
+}
+
+/// Makes the path a part of a pattern
+/// that matches it literally.
+fn escape_word(value: &str) -> String {
+    let mut result = String::new();
+
+    for symbol in value.chars() {
+        if EXPANDED_SYMBOLS.contains(symbol) {
+            result.push('\\');
+        }
+
+        result.push(symbol);
+    }
+
+    result
+}
+
+/// `~` and `~/path` start at the `home`,
+/// `~user/path` at the home directory of
+/// the user. Unknown users are kept as
+/// they are.
+pub fn expand_tilde(word: &str, home: Option<String>) -> String {
+    if !word.starts_with('~') {
+        return word.to_owned();
+    }
+
+    let end = word.find('/').unwrap_or(word.len());
+    let user = &word[1..end];
+
+    let directory = if user.is_empty() {
+        home
+    } else {
+        find_home_directory(user)
+    };
+
+    match directory {
+        Some(it) => escape_word(&it) + &word[end..],
+        None => word.to_owned(),
+    }
+}
+
+/// The `./` the pattern starts with.
+/// `glob` drops it from the paths, but
+/// it keeps the names that start with
+/// `-` from being read as options.
+fn find_current_directory_prefix(pattern: &str) -> &str {
+    let mut rest = pattern;
+
+    while rest.starts_with("./") {
+        rest = rest[1..].trim_start_matches('/');
+    }
+
+    &pattern[..pattern.len() - rest.len()]
+}
+
+/// Hidden files are only matched by
+/// patterns that start with a dot.
+fn find_matches(pattern: &str) -> Vec<String> {
+    let options = MatchOptions {
+        case_sensitive: true,
+        require_literal_separator: true,
+        require_literal_leading_dot: true,
+    };
+
+    let paths = match glob_with(&to_glob_syntax(pattern), options) {
+        Ok(it) => it,
+        Err(_) => return vec![],
+    };
+
+    let prefix = find_current_directory_prefix(pattern);
+
+    let mut result = paths
+        .filter_map(|it| it.ok())
+        .map(|it| prefix.to_owned() + &it.to_string_lossy())
+        .collect::<Vec<String>>();
+
+    result.sort();
+    result
+}
+
+impl Labeled for PatternValue {
+    fn get_type_name() -> &'static str {
+        PATTERN_TYPE
+    }
+}
+
+impl Value for PatternValue {
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+
+    fn as_any_mut(&mut self) -> &mut dyn Any {
+        self
+    }
+
+    fn duplicate_or_move(&mut self) -> Box<dyn Value> {
+        PatternValue::create(&self.pattern)
+    }
+
+    fn get_type_name(&self) -> &'static str {
+        PATTERN_TYPE
+    }
+
+    fn to_string(&self) -> String {
+        unescape_word(&self.pattern)
+    }
+
+    fn to_boolean(&self) -> bool {
+        !self.pattern.is_empty()
+    }
+
+    fn get(&self, subscripts: &[Box<dyn Value>]) -> Box<dyn Value> {
+        self.to_string_value().get(subscripts)
+    }
+
+    fn set(&self, _subscripts: &[Box<dyn Value>], _value: Box<dyn Value>) -> Box<dyn Value> {
+        unsubscriptable(self)
+    }
+
+    fn unary_plus(&self) -> Box<dyn Value> {
+        self.to_string_value().unary_plus()
+    }
+
+    fn unary_minus(&self) -> Box<dyn Value> {
+        self.to_string_value().unary_minus()
+    }
+
+    fn not(&self) -> Box<dyn Value> {
+        BooleanValue::create(!self.to_boolean())
+    }
+
+    fn power(&self, other: Box<dyn Value>) -> Box<dyn Value> {
+        self.to_string_value().power(other)
+    }
+
+    fn times(&self, other: Box<dyn Value>) -> Box<dyn Value> {
+        self.to_string_value().times(other)
+    }
+
+    fn divide(&self, other: Box<dyn Value>) -> Box<dyn Value> {
+        self.to_string_value().divide(other)
+    }
+
+    fn reminder(&self, other: Box<dyn Value>) -> Box<dyn Value> {
+        self.to_string_value().reminder(other)
+    }
+
+    fn plus(&self, other: Box<dyn Value>) -> Box<dyn Value> {
+        self.to_string_value().plus(other)
+    }
+
+    fn minus(&self, other: Box<dyn Value>) -> Box<dyn Value> {
+        self.to_string_value().minus(other)
+    }
+
+    fn contains(&self, other: Box<dyn Value>) -> Box<BooleanValue> {
+        self.to_string_value().contains(other)
+    }
+
+    fn equals(&self, other: Box<dyn Value>) -> Box<BooleanValue> {
+        BooleanValue::create(self.to_string() == other.to_string())
+    }
+
+    fn compare(&self, other: Box<dyn Value>) -> Option<Ordering> {
+        self.to_string_value().compare(other)
+    }
+}
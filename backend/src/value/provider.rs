@@ -1,8 +1,8 @@
 use crate::value::*;
-use crate::value::none::NoneValue;
-use crate::value::number::NumberValue;
 use crate::value::boolean::BooleanValue;
 
+use std::cmp::Ordering;
+
 pub const PROVIDER_TYPE: &'static str = "Provider";
 
 #[derive(Debug)]
@@ -50,51 +50,55 @@ impl Value for ProviderValue {
     }
 
     fn to_string(&self) -> String {
-        return "[value provider]".to_owned();
+        return self.delegate.to_string();
+    }
+
+    fn to_boolean(&self) -> bool {
+        self.delegate.to_boolean()
     }
 
     fn get(&self, _subscripts: &[Box<dyn Value>]) -> Box<dyn Value> {
-        NoneValue::create()
+        unsubscriptable(self)
     }
 
     fn set(&self, _subscripts: &[Box<dyn Value>], _value: Box<dyn Value>) -> Box<dyn Value> {
-        NoneValue::create()
+        unsubscriptable(self)
     }
 
     fn unary_plus(&self) -> Box<dyn Value> {
-        NoneValue::create()
+        unsupported("+", self)
     }
 
     fn unary_minus(&self) -> Box<dyn Value> {
-        NoneValue::create()
+        unsupported("-", self)
     }
 
     fn not(&self) -> Box<dyn Value> {
-        NoneValue::create()
+        self.delegate.not()
     }
 
-    fn power(&self, _other: Box<dyn Value>) -> Box<dyn Value> {
-        NoneValue::create()
+    fn power(&self, other: Box<dyn Value>) -> Box<dyn Value> {
+        mismatch("^", self, other.as_ref())
     }
 
-    fn times(&self, _other: Box<dyn Value>) -> Box<dyn Value> {
-        NoneValue::create()
+    fn times(&self, other: Box<dyn Value>) -> Box<dyn Value> {
+        mismatch("*", self, other.as_ref())
     }
 
-    fn divide(&self, _other: Box<dyn Value>) -> Box<dyn Value> {
-        NoneValue::create()
+    fn divide(&self, other: Box<dyn Value>) -> Box<dyn Value> {
+        mismatch("/", self, other.as_ref())
     }
 
-    fn reminder(&self, _other: Box<dyn Value>) -> Box<dyn Value> {
-        NoneValue::create()
+    fn reminder(&self, other: Box<dyn Value>) -> Box<dyn Value> {
+        mismatch("%", self, other.as_ref())
     }
 
-    fn plus(&self, _other: Box<dyn Value>) -> Box<dyn Value> {
-        NoneValue::create()
+    fn plus(&self, other: Box<dyn Value>) -> Box<dyn Value> {
+        mismatch("+", self, other.as_ref())
     }
 
-    fn minus(&self, _other: Box<dyn Value>) -> Box<dyn Value> {
-        NoneValue::create()
+    fn minus(&self, other: Box<dyn Value>) -> Box<dyn Value> {
+        mismatch("-", self, other.as_ref())
     }
 
     fn contains(&self, _other: Box<dyn Value>) -> Box<BooleanValue> {
@@ -105,7 +109,7 @@ impl Value for ProviderValue {
         BooleanValue::create(false)
     }
 
-    fn compare(&self, _other: Box<dyn Value>) -> Box<NumberValue> {
-        NumberValue::create(0)
+    fn compare(&self, _other: Box<dyn Value>) -> Option<Ordering> {
+        None
     }
 }
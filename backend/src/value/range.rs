@@ -0,0 +1,157 @@
+use crate::cast;
+
+use crate::value::*;
+use crate::value::number::NumberValue;
+use crate::value::boolean::BooleanValue;
+
+use std::cmp::Ordering;
+
+pub const RANGE_TYPE: &'static str = "Range";
+
+/// Numbers from `start` up to, but not
+/// including, `end`, taken with the `step`.
+#[derive(Clone, Debug)]
+pub struct RangeValue {
+    pub start: i32,
+    pub end: i32,
+    pub step: i32,
+}
+
+impl RangeValue {
+    pub fn new(start: i32, end: i32, step: i32) -> RangeValue {
+        RangeValue {
+            start: start,
+            end: end,
+            step: step,
+        }
+    }
+
+    pub fn create(start: i32, end: i32, step: i32) -> Box<RangeValue> {
+        Box::new(RangeValue::new(start, end, step))
+    }
+
+    pub fn has(&self, number: i32) -> bool {
+        let is_between = if self.step > 0 {
+            self.start <= number && number < self.end
+        } else {
+            self.end < number && number <= self.start
+        };
+
+        is_between && (number - self.start) % self.step == 0
+    }
+
+    /// Lazily produces the numbers.
+    pub fn iter(&self) -> impl Iterator<Item = i32> {
+        let step = self.step;
+        let end = self.end;
+
+        std::iter::successors(Some(self.start), move |it| it.checked_add(step))
+            .take_while(move |it| if step > 0 { *it < end } else { *it > end })
+    }
+}
+
+impl Labeled for RangeValue {
+    fn get_type_name() -> &'static str {
+        RANGE_TYPE
+    }
+}
+
+impl Value for RangeValue {
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+
+    fn as_any_mut(&mut self) -> &mut dyn Any {
+        self
+    }
+
+    fn duplicate_or_move(&mut self) -> Box<dyn Value> {
+        RangeValue::create(self.start, self.end, self.step)
+    }
+
+    fn get_type_name(&self) -> &'static str {
+        RANGE_TYPE
+    }
+
+    fn to_string(&self) -> String {
+        if self.step == 1 {
+            format!("range({}, {})", self.start, self.end)
+        } else {
+            format!("range({}, {}, {})", self.start, self.end, self.step)
+        }
+    }
+
+    fn to_boolean(&self) -> bool {
+        self.iter().next().is_some()
+    }
+
+    fn get(&self, _subscripts: &[Box<dyn Value>]) -> Box<dyn Value> {
+        unsubscriptable(self)
+    }
+
+    fn set(&self, _subscripts: &[Box<dyn Value>], _value: Box<dyn Value>) -> Box<dyn Value> {
+        unsubscriptable(self)
+    }
+
+    fn unary_plus(&self) -> Box<dyn Value> {
+        unsupported("+", self)
+    }
+
+    fn unary_minus(&self) -> Box<dyn Value> {
+        unsupported("-", self)
+    }
+
+    fn not(&self) -> Box<dyn Value> {
+        BooleanValue::create(!self.to_boolean())
+    }
+
+    fn power(&self, other: Box<dyn Value>) -> Box<dyn Value> {
+        mismatch("^", self, other.as_ref())
+    }
+
+    fn times(&self, other: Box<dyn Value>) -> Box<dyn Value> {
+        mismatch("*", self, other.as_ref())
+    }
+
+    fn divide(&self, other: Box<dyn Value>) -> Box<dyn Value> {
+        mismatch("/", self, other.as_ref())
+    }
+
+    fn reminder(&self, other: Box<dyn Value>) -> Box<dyn Value> {
+        mismatch("%", self, other.as_ref())
+    }
+
+    fn plus(&self, other: Box<dyn Value>) -> Box<dyn Value> {
+        mismatch("+", self, other.as_ref())
+    }
+
+    fn minus(&self, other: Box<dyn Value>) -> Box<dyn Value> {
+        mismatch("-", self, other.as_ref())
+    }
+
+    fn contains(&self, other: Box<dyn Value>) -> Box<BooleanValue> {
+        if let Some(number) = cast! { other => NumberValue } {
+            if let Some(it) = number.to_i32() {
+                return BooleanValue::create(self.has(it));
+            }
+        }
+
+        BooleanValue::create(false)
+    }
+
+    fn equals(&self, other: Box<dyn Value>) -> Box<BooleanValue> {
+        if let Some(range) = cast! { other => RangeValue } {
+            return BooleanValue::create(
+                self.start == range.start &&
+                self.end == range.end &&
+                self.step == range.step
+            );
+        }
+
+        BooleanValue::create(false)
+    }
+
+    fn compare(&self, _other: Box<dyn Value>) -> Option<Ordering> {
+        None
+    }
+}
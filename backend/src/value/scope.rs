@@ -1,15 +1,15 @@
-use std::collections::HashMap;
+use std::collections::{HashMap, HashSet};
 
 use std::env::vars;
 
 use crate::value::*;
 use crate::value::none::NoneValue;
-use crate::value::number::NumberValue;
 use crate::value::boolean::BooleanValue;
 use crate::value::string::StringValue;
 
 use std::rc::Rc;
 use std::cell::RefCell;
+use std::cmp::Ordering;
 
 use helpers::{elvis, some_or};
 
@@ -18,6 +18,9 @@ use crate::{cast_mut};
 pub struct ScopeData {
     pub parent: Option<Rc<RefCell<ScopeData>>>,
     pub properties: HashMap<String, Box<dyn Value>>,
+    /// The properties external commands
+    /// receive as environment variables.
+    pub exported: HashSet<String>,
 }
 
 impl ScopeData {
@@ -27,6 +30,7 @@ impl ScopeData {
         ScopeData {
             parent: parent,
             properties: HashMap::new(),
+            exported: HashSet::new(),
         }
     }
 
@@ -41,6 +45,7 @@ impl ScopeData {
 
         for (key, value) in vars() {
             data.set_value(&key, StringValue::create(&value));
+            data.exported.insert(key);
         }
 
         return Rc::new(RefCell::new(data));
@@ -70,6 +75,65 @@ impl ScopeData {
     pub fn set_value(&mut self, name: &str, value: Box<dyn Value>) {
         self.properties.insert(name.to_owned(), value);
     }
+
+    /// Removes the property from the closest
+    /// scope that has it. Returns false if
+    /// there's no such property.
+    pub fn remove_value(&mut self, name: &str) -> bool {
+        if self.properties.remove(name).is_some() {
+            self.exported.remove(name);
+            return true;
+        }
+
+        if let Some(wrapped) = &self.parent {
+            return wrapped.borrow_mut().remove_value(name);
+        }
+
+        return false;
+    }
+
+    /// The exported properties of this scope
+    /// as they are now. The ones that have
+    /// been removed are skipped.
+    pub fn get_environment(&self) -> Vec<(String, String)> {
+        let mut result = self.exported.iter()
+            .filter_map(|name| {
+                self.properties.get(name).map(|value| (name.clone(), value.to_string()))
+            })
+            .collect::<Vec<(String, String)>>();
+
+        result.sort();
+        result
+    }
+
+    /// Whether this scope or one of the
+    /// enclosing ones has the property.
+    pub fn has_value(&self, name: &str) -> bool {
+        if self.properties.contains_key(name) {
+            return true;
+        }
+
+        match &self.parent {
+            Some(wrapped) => wrapped.borrow().has_value(name),
+            None => false,
+        }
+    }
+
+    /// Updates the property of the closest
+    /// scope that has it. Returns false if
+    /// there's no such property.
+    pub fn assign_value(&mut self, name: &str, value: Box<dyn Value>) -> bool {
+        if self.properties.contains_key(name) {
+            self.set_value(name, value);
+            return true;
+        }
+
+        if let Some(wrapped) = &self.parent {
+            return wrapped.borrow_mut().assign_value(name, value);
+        }
+
+        return false;
+    }
 }
 
 pub const SCOPE_TYPE: &'static str = "ScopeValue";
@@ -107,6 +171,19 @@ impl ScopeValue {
         data.set_value(name, value);
     }
 
+    /// Updates the variable of the closest scope
+    /// that has it, starting from this one, or
+    /// declares it here if there's none.
+    pub fn assign_or_set_value(&mut self, name: &str, value: Box<dyn Value>) {
+        let mut data = self.data.borrow_mut();
+
+        if data.has_value(name) {
+            data.assign_value(name, value);
+        } else {
+            data.set_value(name, value);
+        }
+    }
+
     pub fn resolve_parts(&mut self, parts: &[String]) -> Option<Box<dyn Value>> {
         if parts.is_empty() {
             return None;
@@ -161,8 +238,8 @@ impl Value for ScopeValue {
     }
 
     fn duplicate_or_move(&mut self) -> Box<dyn Value> {
-        // ScopeValue::create(Some(self.data))
-        NoneValue::create()
+        // scopes are shared rather than copied
+        ScopeValue::create(self.data.clone())
     }
 
     fn get_type_name(&self) -> &'static str {
@@ -173,48 +250,52 @@ impl Value for ScopeValue {
         return "[scope]".to_owned();
     }
 
+    fn to_boolean(&self) -> bool {
+        true
+    }
+
     fn get(&self, _subscripts: &[Box<dyn Value>]) -> Box<dyn Value> {
-        NoneValue::create()
+        unsubscriptable(self)
     }
 
     fn set(&self, _subscripts: &[Box<dyn Value>], _value: Box<dyn Value>) -> Box<dyn Value> {
-        NoneValue::create()
+        unsubscriptable(self)
     }
 
     fn unary_plus(&self) -> Box<dyn Value> {
-        NoneValue::create()
+        unsupported("+", self)
     }
 
     fn unary_minus(&self) -> Box<dyn Value> {
-        NoneValue::create()
+        unsupported("-", self)
     }
 
     fn not(&self) -> Box<dyn Value> {
-        NoneValue::create()
+        BooleanValue::create(false)
     }
 
-    fn power(&self, _other: Box<dyn Value>) -> Box<dyn Value> {
-        NoneValue::create()
+    fn power(&self, other: Box<dyn Value>) -> Box<dyn Value> {
+        mismatch("^", self, other.as_ref())
     }
 
-    fn times(&self, _other: Box<dyn Value>) -> Box<dyn Value> {
-        NoneValue::create()
+    fn times(&self, other: Box<dyn Value>) -> Box<dyn Value> {
+        mismatch("*", self, other.as_ref())
     }
 
-    fn divide(&self, _other: Box<dyn Value>) -> Box<dyn Value> {
-        NoneValue::create()
+    fn divide(&self, other: Box<dyn Value>) -> Box<dyn Value> {
+        mismatch("/", self, other.as_ref())
     }
 
-    fn reminder(&self, _other: Box<dyn Value>) -> Box<dyn Value> {
-        NoneValue::create()
+    fn reminder(&self, other: Box<dyn Value>) -> Box<dyn Value> {
+        mismatch("%", self, other.as_ref())
     }
 
-    fn plus(&self, _other: Box<dyn Value>) -> Box<dyn Value> {
-        NoneValue::create()
+    fn plus(&self, other: Box<dyn Value>) -> Box<dyn Value> {
+        mismatch("+", self, other.as_ref())
     }
 
-    fn minus(&self, _other: Box<dyn Value>) -> Box<dyn Value> {
-        NoneValue::create()
+    fn minus(&self, other: Box<dyn Value>) -> Box<dyn Value> {
+        mismatch("-", self, other.as_ref())
     }
 
     fn contains(&self, _other: Box<dyn Value>) -> Box<BooleanValue> {
@@ -225,7 +306,7 @@ impl Value for ScopeValue {
         BooleanValue::create(false)
     }
 
-    fn compare(&self, _other: Box<dyn Value>) -> Box<NumberValue> {
-        NumberValue::create(0)
+    fn compare(&self, _other: Box<dyn Value>) -> Option<Ordering> {
+        None
     }
 }
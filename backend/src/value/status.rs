@@ -0,0 +1,154 @@
+use crate::cast;
+
+use crate::value::*;
+use crate::value::none::NoneValue;
+use crate::value::number::NumberValue;
+use crate::value::boolean::BooleanValue;
+
+use std::process::ExitStatus;
+
+pub const STATUS_TYPE: &'static str = "Status";
+
+/// The result of running an external
+/// pipeline. It's true if the pipeline
+/// has succeeded (exited with 0).
+#[derive(Clone, Debug)]
+pub struct StatusValue {
+    /// The exit code. If the process
+    /// was killed by a signal, it's
+    /// 128 + the signal number, like
+    /// in other shells.
+    pub code: i32,
+    /// The signal that terminated
+    /// the process, if any.
+    pub signal: Option<i32>,
+}
+
+impl StatusValue {
+    pub fn new(code: i32, signal: Option<i32>) -> StatusValue {
+        StatusValue {
+            code: code,
+            signal: signal,
+        }
+    }
+
+    pub fn create(code: i32, signal: Option<i32>) -> Box<StatusValue> {
+        Box::new(StatusValue::new(code, signal))
+    }
+
+    #[cfg(unix)]
+    pub fn from_exit_status(status: &ExitStatus) -> Box<StatusValue> {
+        use std::os::unix::process::ExitStatusExt;
+
+        if let Some(signal) = status.signal() {
+            return StatusValue::create(128 + signal, Some(signal));
+        }
+
+        StatusValue::create(status.code().unwrap_or(1), None)
+    }
+
+    #[cfg(not(unix))]
+    pub fn from_exit_status(status: &ExitStatus) -> Box<StatusValue> {
+        StatusValue::create(status.code().unwrap_or(1), None)
+    }
+
+    pub fn is_success(&self) -> bool {
+        self.code == 0
+    }
+}
+
+impl Labeled for StatusValue {
+    fn get_type_name() -> &'static str {
+        STATUS_TYPE
+    }
+}
+
+impl Value for StatusValue {
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+
+    fn as_any_mut(&mut self) -> &mut dyn Any {
+        self
+    }
+
+    fn duplicate_or_move(&mut self) -> Box<dyn Value> {
+        StatusValue::create(self.code, self.signal)
+    }
+
+    fn get_type_name(&self) -> &'static str {
+        STATUS_TYPE
+    }
+
+    fn to_string(&self) -> String {
+        return self.code.to_string();
+    }
+
+    fn to_boolean(&self) -> bool {
+        self.is_success()
+    }
+
+    fn get(&self, _subscripts: &[Box<dyn Value>]) -> Box<dyn Value> {
+        NoneValue::create()
+    }
+
+    fn set(&self, _subscripts: &[Box<dyn Value>], _value: Box<dyn Value>) -> Box<dyn Value> {
+        NoneValue::create()
+    }
+
+    fn unary_plus(&self) -> Box<dyn Value> {
+        unsupported("+", self)
+    }
+
+    fn unary_minus(&self) -> Box<dyn Value> {
+        unsupported("-", self)
+    }
+
+    fn not(&self) -> Box<dyn Value> {
+        BooleanValue::create(!self.is_success())
+    }
+
+    fn power(&self, other: Box<dyn Value>) -> Box<dyn Value> {
+        mismatch("^", self, other.as_ref())
+    }
+
+    fn times(&self, other: Box<dyn Value>) -> Box<dyn Value> {
+        mismatch("*", self, other.as_ref())
+    }
+
+    fn divide(&self, other: Box<dyn Value>) -> Box<dyn Value> {
+        mismatch("/", self, other.as_ref())
+    }
+
+    fn reminder(&self, other: Box<dyn Value>) -> Box<dyn Value> {
+        mismatch("%", self, other.as_ref())
+    }
+
+    fn plus(&self, other: Box<dyn Value>) -> Box<dyn Value> {
+        mismatch("+", self, other.as_ref())
+    }
+
+    fn minus(&self, other: Box<dyn Value>) -> Box<dyn Value> {
+        mismatch("-", self, other.as_ref())
+    }
+
+    fn contains(&self, _other: Box<dyn Value>) -> Box<BooleanValue> {
+        BooleanValue::create(false)
+    }
+
+    fn equals(&self, other: Box<dyn Value>) -> Box<BooleanValue> {
+        if let Some(status) = cast! { other => StatusValue } {
+            return BooleanValue::create(self.code == status.code);
+        }
+
+        if let Some(number) = cast! { other => NumberValue } {
+            return BooleanValue::create(number.to_i32() == Some(self.code));
+        }
+
+        BooleanValue::create(false)
+    }
+
+    fn compare(&self, other: Box<dyn Value>) -> Option<Ordering> {
+        NumberValue::create(self.code).compare(other)
+    }
+}
@@ -2,8 +2,15 @@ use crate::cast;
 
 use crate::value::*;
 use crate::value::none::NoneValue;
-use crate::value::number::NumberValue;
+use crate::value::number::{NumberValue, to_numeric};
+use crate::value::float::FloatValue;
+use crate::value::pattern::PatternValue;
 use crate::value::boolean::BooleanValue;
+use crate::value::error::{ErrorValue, ARITHMETIC_ERROR};
+
+use helpers::{elvis, some_or};
+
+use num_traits::{Signed, ToPrimitive};
 
 pub const STRING_TYPE: &'static str = "String";
 
@@ -51,6 +58,10 @@ impl Value for StringValue {
         return self.value.clone();
     }
 
+    fn to_boolean(&self) -> bool {
+        !self.value.is_empty()
+    }
+
     fn get(&self, subscripts: &[Box<dyn Value>]) -> Box<dyn Value> {
         if subscripts.len() != 1 {
             return NoneValue::create();
@@ -59,7 +70,7 @@ impl Value for StringValue {
         let maybe_number_value = cast! { &subscripts[0] => NumberValue };
 
         if let Some(number_value) = maybe_number_value {
-            let mut value = number_value.value;
+            let mut value = some_or! { number_value.to_i32() => i32::MAX };
 
             if value >= self.value.len() as i32 {
                 return NoneValue::create();
@@ -86,7 +97,7 @@ impl Value for StringValue {
     }
 
     fn set(&self, _subscripts: &[Box<dyn Value>], _value: Box<dyn Value>) -> Box<dyn Value> {
-        NoneValue::create()
+        unsubscriptable(self)
     }
 
     fn unary_plus(&self) -> Box<dyn Value> {
@@ -106,41 +117,39 @@ impl Value for StringValue {
     }
 
     fn not(&self) -> Box<dyn Value> {
-        NoneValue::create()
+        BooleanValue::create(self.value.is_empty())
     }
 
-    fn power(&self, _other: Box<dyn Value>) -> Box<dyn Value> {
-        NoneValue::create()
+    fn power(&self, other: Box<dyn Value>) -> Box<dyn Value> {
+        mismatch("^", self, other.as_ref())
     }
 
     fn times(&self, other: Box<dyn Value>) -> Box<dyn Value> {
         let maybe_number_value = cast! { other => NumberValue };
 
         if let Some(number_value) = maybe_number_value {
-            if number_value.value < 0 {
-                return NoneValue::create()
+            if number_value.value.is_negative() {
+                return ErrorValue::create(ARITHMETIC_ERROR, "Can't repeat a string a negative number of times")
             }
 
-            let mut repeated = self.value.clone();
-
-            for _ in 1..number_value.value {
-                repeated += &self.value;
-            }
+            let count = some_or! { number_value.value.to_usize() => {
+                return ErrorValue::create(ARITHMETIC_ERROR, "Can't repeat a string that many times");
+            }};
 
             return StringValue::create(
-                &repeated
+                &self.value.repeat(count)
             );
         }
 
-        return NoneValue::create();
+        return mismatch("*", self, other.as_ref());
     }
 
-    fn divide(&self, _other: Box<dyn Value>) -> Box<dyn Value> {
-        NoneValue::create()
+    fn divide(&self, other: Box<dyn Value>) -> Box<dyn Value> {
+        mismatch("/", self, other.as_ref())
     }
 
-    fn reminder(&self, _other: Box<dyn Value>) -> Box<dyn Value> {
-        NoneValue::create()
+    fn reminder(&self, other: Box<dyn Value>) -> Box<dyn Value> {
+        mismatch("%", self, other.as_ref())
     }
 
     fn plus(&self, other: Box<dyn Value>) -> Box<dyn Value> {
@@ -151,8 +160,8 @@ impl Value for StringValue {
         );
     }
 
-    fn minus(&self, _other: Box<dyn Value>) -> Box<dyn Value> {
-        NoneValue::create()
+    fn minus(&self, other: Box<dyn Value>) -> Box<dyn Value> {
+        mismatch("-", self, other.as_ref())
     }
 
     fn contains(&self, other: Box<dyn Value>) -> Box<BooleanValue> {
@@ -162,18 +171,33 @@ impl Value for StringValue {
     }
 
     fn equals(&self, other: Box<dyn Value>) -> Box<BooleanValue> {
+        let is_number = cast! { other => NumberValue }.is_some() || cast! { other => FloatValue }.is_some();
+
+        if is_number {
+            return match to_numeric(self) {
+                Some(it) => it.to_value().equals(other),
+                None => BooleanValue::create(false),
+            };
+        }
+
         BooleanValue::create(
             self.value == other.to_string()
         )
     }
 
-    fn compare(&self, other: Box<dyn Value>) -> Box<NumberValue> {
-        let that = match self.value.len() - other.to_string().len() {
-            0 => 0,
-            it if it > 0 => 1,
-            _ => -1,
-        };
+    fn compare(&self, other: Box<dyn Value>) -> Option<Ordering> {
+        let is_number = cast! { other => NumberValue }.is_some() || cast! { other => FloatValue }.is_some();
+
+        if is_number {
+            return to_numeric(self)?.to_value().compare(other);
+        }
+
+        let is_text = cast! { other => StringValue }.is_some() || cast! { other => PatternValue }.is_some();
+
+        if is_text {
+            return Some(self.value.as_str().cmp(&other.to_string()));
+        }
 
-        NumberValue::create(that)
+        None
     }
 }
@@ -370,6 +370,94 @@ fn render_extractor_for(node: &NodeInfo) -> String {
     return render(CONCRETE_EXTRACTOR, 0, &[&node_name, &node_name, &visit, &node_name, &node_name]);
 }
 
+/// Template for the struct that makes
+/// deep copies of nodes, and a shortcut
+/// function for using it.
+const REPLICATOR: &'static str = "
+    pub struct Replicator {
+        pub result: Option<Box<dyn Node>>,
+    }
+
+    /// Returns a deep copy of the node.
+    pub fn replicate(node: &mut Box<dyn Node>) -> Box<dyn Node> {
+        let mut replicator = Replicator {
+            result: None,
+        };
+
+        node.accept_simple_visitor(&mut replicator);
+
+        return replicator.result
+            .expect(\"replicating a node\");
+    }
+";
+
+/// Template for a node copy.
+const REPLICATION: &'static str = "
+    self.result = Some(Box::new(
+        $$ {
+            $$
+        }
+    ));
+";
+
+/// Template for copying a field that
+/// holds a node.
+const REPLICATE_SIMPLE_FIELD: &'static str = "
+    $$: replicate(&mut it.$$),
+";
+
+/// Template for copying a list of nodes.
+const REPLICATE_LIST_FIELD: &'static str = "
+    $$: it.$$.iter_mut().map(|that| replicate(that)).collect(),
+";
+
+/// Template for copying an `Option<Node>`.
+const REPLICATE_OPTION_NODE_FIELD: &'static str = "
+    $$: it.$$.as_mut().map(|that| replicate(that)),
+";
+
+/// Template for copying any other field.
+const REPLICATE_CLONE_FIELD: &'static str = "
+    $$: it.$$.clone(),
+";
+
+/// Renders a `SimpleVisitor` impl for the
+/// `Replicator` struct.
+fn render_replicator(nodes: &Vec<NodeInfo>) -> String {
+    let mut methods = vec![];
+
+    for it in nodes {
+        let snake = it.name.to_snake_case();
+        let node_name = "nodes::".to_owned() + &it.name;
+
+        let mut fields = vec![];
+
+        for that in &it.fields {
+            let template = if that.proto.starts_with("Vec") {
+                REPLICATE_LIST_FIELD
+            } else if that.proto == "Option<Box<dyn crate::ast::Node>>" {
+                REPLICATE_OPTION_NODE_FIELD
+            } else if that.proto == "Box<dyn crate::ast::Node>" {
+                REPLICATE_SIMPLE_FIELD
+            } else {
+                REPLICATE_CLONE_FIELD
+            };
+
+            fields.push(render(template, 0, &[&that.name, &that.name]));
+        }
+
+        let contents = render(REPLICATION, 4, &[&node_name, &fields.join("\n        ")]);
+        let visit = render(&VISIT_TEMPLATE_WITH_IT, 4, &[&snake, &node_name, "", "", &contents]);
+
+        methods.push(visit);
+    }
+
+    return vec![
+        render(REPLICATOR, 0, &[]),
+        render_impl("SimpleVisitor", "Replicator", &methods.join("\n\n"), 0),
+    ].join("\n\n");
+}
+
 /// Renders nodes, visitors, and other
 /// corresponding stuff.
 pub fn render_ast(nodes: &Vec<NodeInfo>, visitors: &Vec<VisitorInfo>) -> String {
@@ -391,6 +479,7 @@ pub fn render_ast(nodes: &Vec<NodeInfo>, visitors: &Vec<VisitorInfo>) -> String
 
     pieces.push(render_struct_no_body("ASTPrinter", 0));
     pieces.push(render_ast_printer(nodes));
+    pieces.push(render_replicator(nodes));
     pieces.push(render_extractor_struct_and_impl());
 
     for it in nodes {
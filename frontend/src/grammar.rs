@@ -1,6 +1,6 @@
 include!(concat!(env!("OUT_DIR"), "/grammar.rs"));
 
-use crate::lexer::Token;
+use crate::lexer::{Token, is_glob, unescape_word};
 
 use crate::ast::*;
 use crate::ast::nodes::*;
@@ -34,6 +34,13 @@ fn handle_token(token: &Token) -> Box<dyn Node> {
                 }
             )
         }
+        Token::Newline { line } => {
+            Box::new(
+                LineBreak {
+                    line: *line + 1
+                }
+            )
+        }
         _ => {
             Box::new(
                 Text {
@@ -92,6 +99,16 @@ fn extract_value(mut target: Box<dyn Node>) -> String {
         return result;
     }
 
+    let mut extractor = Extractor::new(|_: &mut LineBreak| {
+        result += "\n";
+    });
+
+    target.accept_simple_visitor(&mut extractor);
+
+    if !result.is_empty() {
+        return result;
+    }
+
     let mut extractor = Extractor::new(|it: &mut Text| {
         result += &it.value;
     });
@@ -101,18 +118,21 @@ fn extract_value(mut target: Box<dyn Node>) -> String {
 }
 
 fn handle_binary_long(mut pattern: Vec<Box<dyn Node>>) -> Box<dyn Node> {
-    if pattern.len() == 4 {
+    if pattern.len() >= 4 {
         let lefter = pattern.remove(0);
-        let operator1 = pattern.remove(0);
-        let operator2 = pattern.remove(0);
-        let righter = pattern.remove(0);
+        let righter = pattern.remove(pattern.len() - 1);
+        let mut operator = String::new();
+
+        for it in pattern {
+            operator += &extract_value(it);
+        }
 
         Box::new(
             Binary {
                 lefter: lefter,
                 operator: Box::new(
                     Text {
-                        value: extract_value(operator1) + &extract_value(operator2)
+                        value: operator
                     }
                 ),
                 righter: righter,
@@ -152,12 +172,58 @@ fn handle_text_append(mut pattern: Vec<Box<dyn Node>>) -> Box<dyn Node> {
 
 fn handle_text_part_substitution(mut pattern: Vec<Box<dyn Node>>) -> Box<dyn Node> {
     if pattern.len() == 3 {
-        pattern.remove(1)
+        Box::new(
+            Substitution {
+                target: pattern.remove(1)
+            }
+        )
     } else {
         create_todo("text_part_substitution")
     }
 }
 
+/// `${expr}` is evaluated just like `(expr)`.
+fn handle_text_part_expression(mut pattern: Vec<Box<dyn Node>>) -> Box<dyn Node> {
+    if pattern.len() == 4 {
+        Box::new(
+            Substitution {
+                target: pattern.remove(2)
+            }
+        )
+    } else {
+        create_todo("text_part_expression")
+    }
+}
+
+/// `$name` or `$scope.field`. Only the words
+/// starting with a letter or `_` are names,
+/// so `$5` is kept as it is.
+fn handle_text_part_provider(mut pattern: Vec<Box<dyn Node>>) -> Box<dyn Node> {
+    if pattern.len() == 2 {
+        let name = extract_value(pattern.remove(1));
+
+        if !name.starts_with(|it: char| it.is_ascii_alphabetic() || it == '_') {
+            return Box::new(
+                Text {
+                    value: "$".to_owned() + &name
+                }
+            );
+        }
+
+        Box::new(
+            Interpolation {
+                target: Box::new(
+                    Text {
+                        value: name
+                    }
+                )
+            }
+        )
+    } else {
+        create_todo("text_part_provider")
+    }
+}
+
 fn handle_text_parts_create(mut pattern: Vec<Box<dyn Node>>) -> Box<dyn Node> {
     if pattern.len() == 1 {
         Box::new(
@@ -194,6 +260,31 @@ fn handle_string_pass_middle(mut pattern: Vec<Box<dyn Node>>) -> Box<dyn Node> {
     }
 }
 
+/// Bare words with wildcards or a leading
+/// `~` become patterns, quoted strings
+/// never do.
+fn handle_string_word(mut pattern: Vec<Box<dyn Node>>) -> Box<dyn Node> {
+    if pattern.len() == 1 {
+        let value = extract_value(pattern.remove(0));
+
+        if is_glob(&value) || value.starts_with('~') {
+            return Box::new(
+                Pattern {
+                    value: value
+                }
+            );
+        }
+
+        Box::new(
+            Text {
+                value: unescape_word(&value)
+            }
+        )
+    } else {
+        create_todo("string_word")
+    }
+}
+
 fn handle_closure_arguments_append(mut pattern: Vec<Box<dyn Node>>) -> Box<dyn Node> {
     if pattern.len() == 3 {
         let mut list = pattern.remove(0);
@@ -224,7 +315,11 @@ fn handle_closure_arguments_create(mut pattern: Vec<Box<dyn Node>>) -> Box<dyn N
 
 fn handle_item_substitution(mut pattern: Vec<Box<dyn Node>>) -> Box<dyn Node> {
     if pattern.len() == 3 {
-        pattern.remove(1)
+        Box::new(
+            Substitution {
+                target: pattern.remove(1)
+            }
+        )
     } else {
         create_todo("item_substitution")
     }
@@ -352,7 +447,11 @@ fn handle_item_string_append(mut pattern: Vec<Box<dyn Node>>) -> Box<dyn Node> {
 fn handle_item_substitution_append(mut pattern: Vec<Box<dyn Node>>) -> Box<dyn Node> {
     if pattern.len() == 4 {
         let mut maybe_parts = pattern.remove(0);
-        let inner = pattern.remove(1);
+        let inner: Box<dyn Node> = Box::new(
+            Substitution {
+                target: pattern.remove(1)
+            }
+        );
 
         if check_node!(maybe_parts, TextParts) {
             let mut extractor = Extractor::new(move |it: &mut TextParts| {
@@ -386,6 +485,119 @@ fn handle_item_substitution_append(mut pattern: Vec<Box<dyn Node>>) -> Box<dyn N
 //     }
 // }
 
+fn handle_item_list(mut pattern: Vec<Box<dyn Node>>) -> Box<dyn Node> {
+    if pattern.len() == 3 {
+        pattern.remove(1)
+    } else {
+        create_todo("item_list")
+    }
+}
+
+fn handle_item_list_empty(pattern: Vec<Box<dyn Node>>) -> Box<dyn Node> {
+    if pattern.len() == 2 {
+        Box::new(
+            List {
+                values: vec![]
+            }
+        )
+    } else {
+        create_todo("item_list_empty")
+    }
+}
+
+fn handle_list_values_append(mut pattern: Vec<Box<dyn Node>>) -> Box<dyn Node> {
+    if pattern.len() == 3 {
+        let mut list = pattern.remove(0);
+        let element = pattern.remove(1); // skipping the operator
+
+        let mut extractor = Extractor::new(move |it: &mut List| {
+            it.values.push(element);
+        });
+
+        list.accept_simple_visitor(&mut extractor);
+        list
+    } else {
+        create_todo("list_values_append")
+    }
+}
+
+fn handle_list_values_create(mut pattern: Vec<Box<dyn Node>>) -> Box<dyn Node> {
+    if pattern.len() == 1 {
+        Box::new(
+            List {
+                values: vec![pattern.remove(0)]
+            }
+        )
+    } else {
+        create_todo("list_values_create")
+    }
+}
+
+fn handle_item_map(mut pattern: Vec<Box<dyn Node>>) -> Box<dyn Node> {
+    if pattern.len() == 3 {
+        pattern.remove(1)
+    } else {
+        create_todo("item_map")
+    }
+}
+
+fn handle_item_map_empty(pattern: Vec<Box<dyn Node>>) -> Box<dyn Node> {
+    if pattern.len() == 3 {
+        Box::new(
+            Map {
+                keys: vec![],
+                values: vec![]
+            }
+        )
+    } else {
+        create_todo("item_map_empty")
+    }
+}
+
+fn handle_map_entries_append(mut pattern: Vec<Box<dyn Node>>) -> Box<dyn Node> {
+    if pattern.len() == 5 {
+        let mut map = pattern.remove(0);
+        let key = pattern.remove(1); // skipping the operator
+        let value = pattern.remove(2); // skipping the operator
+
+        let mut extractor = Extractor::new(move |it: &mut Map| {
+            it.keys.push(key);
+            it.values.push(value);
+        });
+
+        map.accept_simple_visitor(&mut extractor);
+        map
+    } else {
+        create_todo("map_entries_append")
+    }
+}
+
+fn handle_map_entries_create(mut pattern: Vec<Box<dyn Node>>) -> Box<dyn Node> {
+    if pattern.len() == 3 {
+        Box::new(
+            Map {
+                keys: vec![pattern.remove(0)],
+                values: vec![pattern.remove(1)] // skipping the operator
+            }
+        )
+    } else {
+        create_todo("map_entries_create")
+    }
+}
+
+fn handle_subscript(mut pattern: Vec<Box<dyn Node>>) -> Box<dyn Node> {
+    if pattern.len() == 4 {
+        Box::new(
+            Subscript {
+                target: pattern.remove(0),
+                index: pattern.remove(1), // skipping the operator
+            }
+        )
+    } else {
+        create_todo("subscript")
+    }
+}
+
 fn handle_provider(mut pattern: Vec<Box<dyn Node>>) -> Box<dyn Node> {
     if pattern.len() == 2 {
         Box::new(
@@ -426,6 +638,16 @@ fn handle_command_create(mut pattern: Vec<Box<dyn Node>>) -> Box<dyn Node> {
     }
 }
 
+/// Keeps all the parts of a command with
+/// a special syntax, like `for x in y {}`.
+fn handle_command_keyword(pattern: Vec<Box<dyn Node>>) -> Box<dyn Node> {
+    Box::new(
+        Command {
+            arguments: pattern
+        }
+    )
+}
+
 fn handle_pipeline_append(mut pattern: Vec<Box<dyn Node>>) -> Box<dyn Node> {
     if pattern.len() == 3 {
         let mut pipeline = pattern.remove(0);
@@ -467,6 +689,18 @@ fn handle_assignment(mut pattern: Vec<Box<dyn Node>>) -> Box<dyn Node> {
     }
 }
 
+fn handle_background(mut pattern: Vec<Box<dyn Node>>) -> Box<dyn Node> {
+    if pattern.len() == 2 {
+        Box::new(
+            Background {
+                value: pattern.remove(0),
+            }
+        )
+    } else {
+        create_todo("background")
+    }
+}
+
 fn handle_expressions_append(mut pattern: Vec<Box<dyn Node>>) -> Box<dyn Node> {
     if pattern.len() == 3 {
         let mut expressions = pattern.remove(0);
@@ -483,6 +717,52 @@ fn handle_expressions_append(mut pattern: Vec<Box<dyn Node>>) -> Box<dyn Node> {
     }
 }
 
+/// The statements that start a line keep
+/// the line break before them, it tells
+/// the runner which line they are at.
+fn handle_expressions_append_line(mut pattern: Vec<Box<dyn Node>>) -> Box<dyn Node> {
+    if pattern.len() == 3 {
+        let mut expressions = pattern.remove(0);
+        let line_break = pattern.remove(0);
+        let element = pattern.remove(0);
+
+        let mut extractor = Extractor::new(move |it: &mut Expressions| {
+            it.values.push(line_break);
+            it.values.push(element);
+        });
+
+        expressions.accept_simple_visitor(&mut extractor);
+        expressions
+    } else {
+        create_todo("expressions_append_line")
+    }
+}
+
+fn handle_expressions_create_line(mut pattern: Vec<Box<dyn Node>>) -> Box<dyn Node> {
+    if pattern.len() >= 2 {
+        let element = pattern.remove(pattern.len() - 1);
+        let line_break = pattern.remove(pattern.len() - 1);
+
+        Box::new(
+            Expressions {
+                values: vec![line_break, element]
+            }
+        )
+    } else {
+        create_todo("expressions_create_line")
+    }
+}
+
+/// Blank lines are skipped, the last
+/// line break is the one that counts.
+fn handle_line_breaks_append(mut pattern: Vec<Box<dyn Node>>) -> Box<dyn Node> {
+    if pattern.len() >= 2 {
+        pattern.remove(pattern.len() - 1)
+    } else {
+        create_todo("line_breaks_append")
+    }
+}
+
 fn handle_expressions_create(mut pattern: Vec<Box<dyn Node>>) -> Box<dyn Node> {
     if pattern.len() == 1 {
         Box::new(
@@ -3,6 +3,8 @@ use parsing::stream::accumulator_stream::*;
 
 use parsing::ruler::{RepresentableToken};
 
+use helpers::{elvis, some_or};
+
 #[derive(Clone, PartialEq, Debug, Eq)]
 pub enum Token {
     Operator {
@@ -11,10 +13,6 @@ pub enum Token {
     Delimiter {
         value: String
     },
-    NumberSegment {
-        value: String,
-        base: u8
-    },
     Number {
         value: String,
         base: u8
@@ -25,7 +23,10 @@ pub enum Token {
     Whitespace {
         value: String
     },
-    Newline,
+    Newline {
+        /// The line the newline ends.
+        line: usize
+    },
     CommandEnd,
     End,
 }
@@ -41,7 +42,6 @@ impl RepresentableToken for Token {
             Token::Newline { .. } => "newline",
             Token::End { .. } => "end",
             Token::CommandEnd { .. } => "commandend",
-            _ => "",
         }.to_owned()
     }
 
@@ -52,7 +52,7 @@ impl RepresentableToken for Token {
             Token::Number { value, .. } => Some(value),
             Token::Text { value } => Some(value),
             Token::Whitespace { value } => Some(value),
-            Token::Newline => Some("\n"),
+            Token::Newline { .. } => Some("\n"),
             _ => None,
         }
     }
@@ -61,7 +61,7 @@ impl RepresentableToken for Token {
 /// Operators are symbols that get clued
 /// to the strings if there's no whitespace
 /// between them
-pub const OPERATORS: &'static str = ":=+-*%#!&^|/.~[]<>;,";
+pub const OPERATORS: &'static str = ":=+-*%#!&^|/.[]<>;,";
 /// Delimiters never clue to strings
 pub const DELIMITERS: &'static str = "()$@\"'{}";
 
@@ -81,23 +81,8 @@ fn is_control(symbol: char) -> bool {
     return is_operator(symbol) || is_delimiter(symbol);
 }
 
-fn is_binary(symbol: char) -> bool {
-    return ('0'..='1').contains(&symbol);
-}
-
-fn is_octal(symbol: char) -> bool {
-    return ('0'..='7').contains(&symbol);
-}
-
 fn is_decimal(symbol: char) -> bool {
-    return ('0'..='9').contains(&symbol);
-}
-
-fn is_hexadecimal(symbol: char) -> bool {
-    return
-        ('0'..='9').contains(&symbol) ||
-        ('a'..='f').contains(&symbol) ||
-        ('A'..='F').contains(&symbol);
+    return symbol.is_ascii_digit();
 }
 
 fn is_text_content(symbol: char) -> bool {
@@ -108,6 +93,175 @@ fn is_text_content(symbol: char) -> bool {
         symbol != '\\';
 }
 
+/// The meaning of `\` followed by the symbol
+/// in bare words and double-quoted strings.
+/// Any other letter or digit keeps the
+/// backslash, like in `C:\Users`.
+fn unescape(symbol: char) -> Option<String> {
+    match symbol {
+        'n' => Some("\n".to_owned()),
+        't' => Some("\t".to_owned()),
+        'r' => Some("\r".to_owned()),
+        '0' => Some("\0".to_owned()),
+        'e' => Some("\x1b".to_owned()),
+        it if it.is_alphanumeric() => None,
+        it => Some(it.to_string()),
+    }
+}
+
+/// Symbols that make a bare word a glob,
+/// and the `~` of home directories.
+/// Escaping keeps them literal, so the
+/// backslash is passed on until the
+/// word is expanded.
+pub const EXPANDED_SYMBOLS: &'static str = "*?[]{}~";
+
+/// Whether the word has an unescaped
+/// wildcard or a brace group.
+pub fn is_glob(value: &str) -> bool {
+    let mut symbols = value.chars();
+
+    while let Some(symbol) = symbols.next() {
+        match symbol {
+            '\\' => { symbols.next(); },
+            '*' | '?' | '[' | '{' => return true,
+            _ => {},
+        }
+    }
+
+    false
+}
+
+/// Drops the backslashes that have been
+/// kept before the expanded symbols.
+pub fn unescape_word(value: &str) -> String {
+    let mut result = String::new();
+    let mut symbols = value.chars().peekable();
+
+    while let Some(symbol) = symbols.next() {
+        match symbols.peek() {
+            Some(&next) if symbol == '\\' && EXPANDED_SYMBOLS.contains(next) => {
+                result.push(next);
+                symbols.next();
+            },
+            _ => result.push(symbol),
+        }
+    }
+
+    result
+}
+
+fn prefix_to_base(prefix: &str) -> Option<u8> {
+    match prefix {
+        "0b" => Some(2),
+        "0o" => Some(8),
+        "0x" => Some(16),
+        _ => None,
+    }
+}
+
+fn suffix_to_base(suffix: &str) -> Option<u8> {
+    match suffix {
+        "b" => Some(2),
+        "o" => Some(8),
+        "h" => Some(16),
+        _ => None,
+    }
+}
+
+/// Drops the `_` separators, they are
+/// only allowed between the digits.
+fn to_digits(value: &str, base: u8) -> Option<String> {
+    if value.is_empty() || value.starts_with('_') || value.ends_with('_') {
+        return None;
+    }
+
+    let digits = value.replace('_', "");
+
+    if digits.chars().all(|it| it.is_digit(base as u32)) {
+        Some(digits)
+    } else {
+        None
+    }
+}
+
+/// Finds the base of `1010b`, `0b1010`,
+/// `0ffh`, `0xff`, `-10` and the like.
+fn to_integer(value: &str) -> Option<(String, u8)> {
+    let with_prefix = value.get(..2)
+        .and_then(prefix_to_base)
+        .and_then(|base| Some((to_digits(&value[2..], base)?, base)));
+
+    if with_prefix.is_some() {
+        return with_prefix;
+    }
+
+    // `each` and `beach` are words,
+    // so `0ffh` needs the leading `0`
+    if !value.starts_with(|it: char| it.is_ascii_digit()) {
+        return None;
+    }
+
+    let split = value.len().saturating_sub(1);
+    let with_suffix = value.get(split..)
+        .and_then(suffix_to_base)
+        .and_then(|base| Some((to_digits(&value[..split], base)?, base)));
+
+    if with_suffix.is_some() {
+        return with_suffix;
+    }
+
+    Some((to_digits(value, 10)?, 10))
+}
+
+fn split_sign(value: &str) -> (&str, &str) {
+    if value.starts_with('+') || value.starts_with('-') {
+        value.split_at(1)
+    } else {
+        ("", value)
+    }
+}
+
+/// Words that look like numbers become
+/// numbers. They keep the text they've
+/// been written with, so that `echo 1.50`
+/// still prints `1.50`.
+fn to_word_token(value: String, is_float: bool) -> Token {
+    let (_, unsigned) = split_sign(&value);
+
+    if let Some((_, base)) = to_integer(unsigned) {
+        return Token::Number {
+            value: value,
+            base: base,
+        };
+    }
+
+    let is_separated = !unsigned.starts_with('_') && !unsigned.ends_with('_');
+
+    if is_float && is_separated && value.replace('_', "").parse::<f64>().is_ok() {
+        return Token::Number {
+            value: value,
+            base: 10,
+        };
+    }
+
+    return Token::Text {
+        value: value
+    };
+}
+
+/// The digits of a number token in its base,
+/// without the separators and the prefix or
+/// the suffix of the base.
+pub fn to_number_digits(value: &str) -> String {
+    let (sign, unsigned) = split_sign(value);
+
+    match to_integer(unsigned) {
+        Some((digits, _)) => sign.to_owned() + &digits,
+        None => value.replace('_', ""),
+    }
+}
+
 /// Splits the input into tokens.
 pub struct Lexer<'a> {
     /// Delegate for all operations.
@@ -121,6 +275,12 @@ pub struct Lexer<'a> {
     /// that represents the current
     /// level of nesting.
     pub nesting_stack: Vec<char>,
+    /// Whether the last token other than
+    /// whitespace ends a value, like `1`,
+    /// `$a`, `)` or `]` do.
+    pub follows_value: bool,
+    /// The line the next token starts at.
+    pub line: usize,
 }
 
 impl <'a> Lexer<'a> {
@@ -138,81 +298,175 @@ impl <'a> Lexer<'a> {
         };
     }
 
+    /// `\` followed by a newline continues the line.
+    /// In single-quoted strings only `\'` and `\\`
+    /// are escapes, elsewhere see `unescape()`.
+    /// Escaped expanded symbols keep the backslash
+    /// outside of double-quoted strings.
     fn read_escape(&mut self) -> Token {
-        let next = self.backend.grab();
+        let next = some_or! { self.backend.peek() => {
+            return Token::Text {
+                value: self.backend.revise_all()
+            };
+        }};
+
+        self.backend.step();
 
-        if next == Some('\n') {
+        if next == '\n' {
             return Token::Whitespace {
                 value: self.backend.revise_all()
             }
         }
 
+        if self.nesting_stack.last() == Some(&'\'') {
+            if next == '\'' || next == '\\' {
+                return Token::Text {
+                    value: next.to_string()
+                };
+            }
+
+            return Token::Text {
+                value: self.backend.revise_all()
+            };
+        }
+
+        if EXPANDED_SYMBOLS.contains(next) && self.nesting_stack.last() != Some(&'"') {
+            return Token::Text {
+                value: self.backend.revise_all()
+            };
+        }
+
+        if next == 'u' {
+            return self.read_unicode_escape();
+        }
+
         return Token::Text {
-            value: self.backend.revise_all()
+            value: some_or! { unescape(next) => self.backend.revise_all() }
         };
     }
 
-    fn read_binary(&mut self) -> Token {
+    /// Reads the `{263A}` part of `\u{263A}`.
+    fn read_unicode_escape(&mut self) -> Token {
+        if !self.backend.accept('{') {
+            return Token::Text {
+                value: self.backend.revise_all()
+            };
+        }
+
         while let Some(symbol) = self.backend.peek() {
-            if is_binary(symbol) {
+            if symbol.is_ascii_hexdigit() {
                 self.backend.step();
             } else {
                 break;
             }
         }
 
-        return Token::NumberSegment {
-            value: self.backend.revise_all(),
-            base: 2,
-        };
+        let closed = self.backend.accept('}');
+        let value = self.backend.revise_all();
+
+        let code = value.get(3..value.len() - 1)
+            .and_then(|it| u32::from_str_radix(it, 16).ok())
+            .and_then(char::from_u32);
+
+        match code {
+            Some(it) if closed => Token::Text {
+                value: it.to_string()
+            },
+            _ => Token::Text {
+                value: value
+            },
+        }
     }
 
-    fn read_octal(&mut self) -> Token {
+    /// Reads a whole literal, so that `0x1f`,
+    /// `1fh`, `1_000` and `1.5e-3` become
+    /// single tokens.
+    fn read_number(&mut self) -> Token {
+        self.read_decimal_digits();
+
+        let has_fraction = self.read_fraction();
+        let has_exponent = self.read_exponent();
+
+        self.read_text_content();
+        return to_word_token(self.backend.revise_all(), has_fraction || has_exponent);
+    }
+
+    fn read_decimal_digits(&mut self) {
         while let Some(symbol) = self.backend.peek() {
-            if is_octal(symbol) {
+            if is_decimal(symbol) || symbol == '_' {
                 self.backend.step();
             } else {
                 break;
             }
         }
+    }
+
+    /// Reads `.5` in `1.5`, but not the
+    /// dots in `1.txt` or `1..2`.
+    fn read_fraction(&mut self) -> bool {
+        if self.backend.peek() != Some('.') {
+            return false;
+        }
 
-        return Token::NumberSegment {
-            value: self.backend.revise_all(),
-            base: 8,
-        };
+        if !self.backend.lookahead(1).is_some_and(is_decimal) {
+            return false;
+        }
+
+        self.backend.step();
+        self.read_decimal_digits();
+        return true;
     }
 
-    fn read_decimal(&mut self) -> Token {
-        while let Some(symbol) = self.backend.peek() {
-            if is_decimal(symbol) {
-                self.backend.step();
-            } else {
-                break;
-            }
+    /// Reads `e10`, `e+10` or `e-10`.
+    fn read_exponent(&mut self) -> bool {
+        if self.backend.peek() != Some('e') && self.backend.peek() != Some('E') {
+            return false;
         }
 
-        return Token::NumberSegment {
-            value: self.backend.revise_all(),
-            base: 10,
+        let sign = self.backend.lookahead(1);
+        let has_sign = sign == Some('+') || sign == Some('-');
+        let digit = if has_sign {
+            self.backend.lookahead(2)
+        } else {
+            sign
         };
+
+        if !digit.is_some_and(is_decimal) {
+            return false;
+        }
+
+        self.backend.step();
+
+        if has_sign {
+            self.backend.step();
+        }
+
+        self.read_decimal_digits();
+        return true;
     }
 
-    fn read_hexadecimal(&mut self) -> Token {
-        while let Some(symbol) = self.backend.peek() {
-            if is_decimal(symbol) {
-                self.backend.step();
-            } else {
-                break;
-            }
+    /// `+1` and `-1` are numbers unless the
+    /// sign follows a value, like in `a-1`,
+    /// `(1)-1` or `(5 -1)`.
+    fn is_number_sign(&mut self, symbol: char) -> bool {
+        if symbol != '+' && symbol != '-' {
+            return false;
         }
 
-        return Token::NumberSegment {
-            value: self.backend.revise_all(),
-            base: 16,
-        };
+        if !self.backend.lookahead(1).is_some_and(is_decimal) {
+            return false;
+        }
+
+        match &self.last_token {
+            Token::Whitespace { .. } if self.nesting_stack.last() == Some(&'(') => !self.follows_value,
+            Token::Whitespace { .. } | Token::Newline { .. } | Token::CommandEnd => true,
+            Token::Operator { value } => value != "]",
+            Token::Delimiter { value } => value == "(" || value == "{",
+            _ => false,
+        }
     }
 
-    fn read_text(&mut self) -> Token {
+    fn read_text_content(&mut self) {
         while let Some(symbol) = self.backend.peek() {
             if is_text_content(symbol) {
                 self.backend.step();
@@ -220,10 +474,11 @@ impl <'a> Lexer<'a> {
                 break;
             }
         }
+    }
 
-        return Token::Text {
-            value: self.backend.revise_all()
-        };
+    fn read_text(&mut self) -> Token {
+        self.read_text_content();
+        return to_word_token(self.backend.revise_all(), false);
     }
 
     fn update_nesting_stack(&mut self, symbol: char) {
@@ -291,7 +546,9 @@ impl <'a> Lexer<'a> {
             return if let None = self.nesting_stack.last() {
                 Token::CommandEnd
             } else {
-                Token::Newline
+                Token::Newline {
+                    line: self.line
+                }
             }
         }
 
@@ -301,6 +558,11 @@ impl <'a> Lexer<'a> {
                 return self.read_whitespace();
             }
 
+            if self.is_number_sign(symbol) {
+                self.backend.step();
+                return self.read_number();
+            }
+
             if is_operator(symbol) {
                 self.update_nesting_stack(symbol);
                 self.backend.step();
@@ -317,24 +579,8 @@ impl <'a> Lexer<'a> {
                 };
             }
 
-            if is_binary(symbol) {
-                self.backend.step();
-                return self.read_binary();
-            }
-
-            if is_octal(symbol) {
-                self.backend.step();
-                return self.read_octal();
-            }
-
             if is_decimal(symbol) {
-                self.backend.step();
-                return self.read_decimal();
-            }
-
-            if is_hexadecimal(symbol) {
-                self.backend.step();
-                return self.read_hexadecimal();
+                return self.read_number();
             }
 
             self.backend.step();
@@ -349,9 +595,11 @@ impl <'a> Lexer<'a> {
     ) -> Lexer<'a> {
         return Lexer::<'a> {
             backend: backend,
-            last_token: Token::Newline,
+            last_token: Token::Newline { line: 0 },
             last_token_offset: 0,
             nesting_stack: vec![],
+            follows_value: false,
+            line: 1,
         };
     }
 }
@@ -363,7 +611,24 @@ impl <'a> Stream<Token> for Lexer<'a> {
 
     fn grab(&mut self) -> Token {
         self.last_token_offset = self.backend.get_offset();
-        self.last_token = self.read_item();
+        let token = self.read_item();
+
+        self.follows_value = match &token {
+            Token::Whitespace { .. } => self.follows_value,
+            Token::Number { .. } => true,
+            Token::Text { .. } => self.last_token == Token::Delimiter { value: "$".to_owned() },
+            Token::Operator { value } => value == "]",
+            Token::Delimiter { value } => value == ")",
+            _ => false,
+        };
+
+        match &token {
+            Token::Newline { .. } | Token::CommandEnd => self.line += 1,
+            Token::Whitespace { value } => self.line += value.matches('\n').count(),
+            _ => {},
+        }
+
+        self.last_token = token;
         return self.last_token.clone();
     }
 
@@ -89,7 +89,7 @@ mod tests {
 
     #[test]
     fn test_lexer_numeric() {
-        assert_tokens("10 10b 10o 10h 3 3b 9 9b 9o f fb fh", &[
+        assert_tokens("10 10b 10o 10h 3 3b 9 9b 9o f fb fh 0fh", &[
             Token::Number {
                 value: "10".to_owned(),
                 base: 10,
@@ -98,21 +98,21 @@ mod tests {
                 value: " ".to_owned()
             },
             Token::Number {
-                value: "10".to_owned(),
+                value: "10b".to_owned(),
                 base: 2,
             },
             Token::Whitespace {
                 value: " ".to_owned()
             },
             Token::Number {
-                value: "10".to_owned(),
+                value: "10o".to_owned(),
                 base: 8,
             },
             Token::Whitespace {
                 value: " ".to_owned()
             },
             Token::Number {
-                value: "10".to_owned(),
+                value: "10h".to_owned(),
                 base: 16,
             },
             Token::Whitespace {
@@ -162,14 +162,183 @@ mod tests {
             Token::Whitespace {
                 value: " ".to_owned()
             },
+            Token::Text {
+                value: "fh".to_owned()
+            },
+            Token::Whitespace {
+                value: " ".to_owned()
+            },
             Token::Number {
-                value: "f".to_owned(),
+                value: "0fh".to_owned(),
                 base: 16,
             },
             Token::End,
         ]);
     }
 
+    #[test]
+    fn test_lexer_radix() {
+        assert_tokens("0b101 0o17 0x1F 1fh 0xg 1_000 1__0 1_ -0x10 -7o a-1 [-1]", &[
+            Token::Number {
+                value: "0b101".to_owned(),
+                base: 2,
+            },
+            Token::Whitespace {
+                value: " ".to_owned()
+            },
+            Token::Number {
+                value: "0o17".to_owned(),
+                base: 8,
+            },
+            Token::Whitespace {
+                value: " ".to_owned()
+            },
+            Token::Number {
+                value: "0x1F".to_owned(),
+                base: 16,
+            },
+            Token::Whitespace {
+                value: " ".to_owned()
+            },
+            Token::Number {
+                value: "1fh".to_owned(),
+                base: 16,
+            },
+            Token::Whitespace {
+                value: " ".to_owned()
+            },
+            Token::Text {
+                value: "0xg".to_owned()
+            },
+            Token::Whitespace {
+                value: " ".to_owned()
+            },
+            Token::Number {
+                value: "1_000".to_owned(),
+                base: 10,
+            },
+            Token::Whitespace {
+                value: " ".to_owned()
+            },
+            Token::Number {
+                value: "1__0".to_owned(),
+                base: 10,
+            },
+            Token::Whitespace {
+                value: " ".to_owned()
+            },
+            Token::Text {
+                value: "1_".to_owned()
+            },
+            Token::Whitespace {
+                value: " ".to_owned()
+            },
+            Token::Number {
+                value: "-0x10".to_owned(),
+                base: 16,
+            },
+            Token::Whitespace {
+                value: " ".to_owned()
+            },
+            Token::Number {
+                value: "-7o".to_owned(),
+                base: 8,
+            },
+            Token::Whitespace {
+                value: " ".to_owned()
+            },
+            Token::Text {
+                value: "a".to_owned()
+            },
+            Token::Operator {
+                value: "-".to_owned()
+            },
+            Token::Number {
+                value: "1".to_owned(),
+                base: 10,
+            },
+            Token::Whitespace {
+                value: " ".to_owned()
+            },
+            Token::Operator {
+                value: "[".to_owned()
+            },
+            Token::Number {
+                value: "-1".to_owned(),
+                base: 10,
+            },
+            Token::Operator {
+                value: "]".to_owned()
+            },
+            Token::End,
+        ]);
+    }
+
+    #[test]
+    fn test_lexer_signs_after_values() {
+        assert_tokens("(5 -1) -1", &[
+            Token::Delimiter {
+                value: "(".to_owned()
+            },
+            Token::Number {
+                value: "5".to_owned(),
+                base: 10,
+            },
+            Token::Whitespace {
+                value: " ".to_owned()
+            },
+            Token::Operator {
+                value: "-".to_owned()
+            },
+            Token::Number {
+                value: "1".to_owned(),
+                base: 10,
+            },
+            Token::Delimiter {
+                value: ")".to_owned()
+            },
+            Token::Whitespace {
+                value: " ".to_owned()
+            },
+            Token::Number {
+                value: "-1".to_owned(),
+                base: 10,
+            },
+            Token::End,
+        ]);
+    }
+
+    #[test]
+    fn test_lexer_suffix_words() {
+        assert_tokens("each beach bach $each", &[
+            Token::Text {
+                value: "each".to_owned()
+            },
+            Token::Whitespace {
+                value: " ".to_owned()
+            },
+            Token::Text {
+                value: "beach".to_owned()
+            },
+            Token::Whitespace {
+                value: " ".to_owned()
+            },
+            Token::Text {
+                value: "bach".to_owned()
+            },
+            Token::Whitespace {
+                value: " ".to_owned()
+            },
+            Token::Delimiter {
+                value: "$".to_owned()
+            },
+            Token::Text {
+                value: "each".to_owned()
+            },
+            Token::End,
+        ]);
+    }
+
     #[test]
     fn test_lexer_arithmetics() {
         assert_tokens("a + b a+b a+ +b 1+2 2+ +1 +++ a(b) a-b- a+7 7+b", &[
@@ -214,21 +383,21 @@ mod tests {
             },
             Token::Number {
                 value: "1".to_owned(),
-                base: 10
+                base: 10,
             },
             Token::Operator {
                 value: "+".to_owned()
             },
             Token::Number {
                 value: "2".to_owned(),
-                base: 10
+                base: 10,
             },
             Token::Whitespace {
                 value: " ".to_owned()
             },
             Token::Number {
                 value: "2".to_owned(),
-                base: 10
+                base: 10,
             },
             Token::Operator {
                 value: "+".to_owned()
@@ -236,12 +405,9 @@ mod tests {
             Token::Whitespace {
                 value: " ".to_owned()
             },
-            Token::Operator {
-                value: "+".to_owned()
-            },
             Token::Number {
-                value: "1".to_owned(),
-                base: 10
+                value: "+1".to_owned(),
+                base: 10,
             },
             Token::Whitespace {
                 value: " ".to_owned()
@@ -290,7 +456,7 @@ mod tests {
             },
             Token::Number {
                 value: "7".to_owned(),
-                base: 10
+                base: 10,
             },
             Token::Whitespace {
                 value: " ".to_owned()
@@ -339,4 +505,219 @@ mod tests {
             Token::End,
         ]);
     }
+
+    #[test]
+    fn test_lexer_brackets() {
+        assert_tokens("$list[a]", &[
+            Token::Delimiter {
+                value: "$".to_owned()
+            },
+            Token::Text {
+                value: "list".to_owned()
+            },
+            Token::Operator {
+                value: "[".to_owned()
+            },
+            Token::Text {
+                value: "a".to_owned()
+            },
+            Token::Operator {
+                value: "]".to_owned()
+            },
+            Token::End,
+        ]);
+    }
+
+    #[test]
+    fn test_lexer_floats() {
+        assert_tokens("1.5 2e3 12.5e-1 1.txt", &[
+            Token::Number {
+                value: "1.5".to_owned(),
+                base: 10,
+            },
+            Token::Whitespace {
+                value: " ".to_owned()
+            },
+            Token::Number {
+                value: "2e3".to_owned(),
+                base: 10,
+            },
+            Token::Whitespace {
+                value: " ".to_owned()
+            },
+            Token::Number {
+                value: "12.5e-1".to_owned(),
+                base: 10,
+            },
+            Token::Whitespace {
+                value: " ".to_owned()
+            },
+            Token::Text {
+                value: "1.txt".to_owned()
+            },
+            Token::End,
+        ]);
+    }
+
+    #[test]
+    fn test_lexer_raw_numbers() {
+        assert_tokens("echo 2021_01_01 1.50 2021_01_01.txt", &[
+            Token::Text {
+                value: "echo".to_owned()
+            },
+            Token::Whitespace {
+                value: " ".to_owned()
+            },
+            Token::Number {
+                value: "2021_01_01".to_owned(),
+                base: 10,
+            },
+            Token::Whitespace {
+                value: " ".to_owned()
+            },
+            Token::Number {
+                value: "1.50".to_owned(),
+                base: 10,
+            },
+            Token::Whitespace {
+                value: " ".to_owned()
+            },
+            Token::Text {
+                value: "2021_01_01.txt".to_owned()
+            },
+            Token::End,
+        ]);
+    }
+
+    #[test]
+    fn test_lexer_escapes() {
+        assert_tokens(r#""a\tb\u{263A}\"\$" 'c\'\n' d\|e\ f C:\Users"#, &[
+            Token::Delimiter {
+                value: "\"".to_owned()
+            },
+            Token::Text {
+                value: "a\tb\u{263A}\"$".to_owned()
+            },
+            Token::Delimiter {
+                value: "\"".to_owned()
+            },
+            Token::Whitespace {
+                value: " ".to_owned()
+            },
+            Token::Delimiter {
+                value: "'".to_owned()
+            },
+            Token::Text {
+                value: "c'\\n".to_owned()
+            },
+            Token::Delimiter {
+                value: "'".to_owned()
+            },
+            Token::Whitespace {
+                value: " ".to_owned()
+            },
+            Token::Text {
+                value: "d|e f".to_owned()
+            },
+            Token::Whitespace {
+                value: " ".to_owned()
+            },
+            Token::Text {
+                value: "C:\\Users".to_owned()
+            },
+            Token::End,
+        ]);
+    }
+
+    #[test]
+    fn test_lexer_globs() {
+        assert_tokens("ls src/* [ab]?.rs x{a,b} \\* (2 * 3)", &[
+            Token::Text {
+                value: "ls".to_owned()
+            },
+            Token::Whitespace {
+                value: " ".to_owned()
+            },
+            Token::Text {
+                value: "src/*".to_owned()
+            },
+            Token::Whitespace {
+                value: " ".to_owned()
+            },
+            Token::Text {
+                value: "[ab]?.rs".to_owned()
+            },
+            Token::Whitespace {
+                value: " ".to_owned()
+            },
+            Token::Text {
+                value: "x{a,b}".to_owned()
+            },
+            Token::Whitespace {
+                value: " ".to_owned()
+            },
+            Token::Text {
+                value: "\\*".to_owned()
+            },
+            Token::Whitespace {
+                value: " ".to_owned()
+            },
+            Token::Delimiter {
+                value: "(".to_owned()
+            },
+            Token::Number {
+                value: "2".to_owned(),
+                base: 10,
+            },
+            Token::Whitespace {
+                value: " ".to_owned()
+            },
+            Token::Operator {
+                value: "*".to_owned()
+            },
+            Token::Whitespace {
+                value: " ".to_owned()
+            },
+            Token::Number {
+                value: "3".to_owned(),
+                base: 10,
+            },
+            Token::Delimiter {
+                value: ")".to_owned()
+            },
+            Token::End,
+        ]);
+    }
+
+    #[test]
+    fn test_lexer_trailing_operators() {
+        assert_tokens("cd -", &[
+            Token::Text {
+                value: "cd".to_owned()
+            },
+            Token::Whitespace {
+                value: " ".to_owned()
+            },
+            Token::Text {
+                value: "-".to_owned()
+            },
+            Token::End,
+        ]);
+    }
+
+    #[test]
+    fn test_lexer_job_references() {
+        assert_tokens("fg %2", &[
+            Token::Text {
+                value: "fg".to_owned()
+            },
+            Token::Whitespace {
+                value: " ".to_owned()
+            },
+            Token::Text {
+                value: "%2".to_owned()
+            },
+            Token::End,
+        ]);
+    }
 }
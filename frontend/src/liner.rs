@@ -2,6 +2,8 @@ use parsing::stream::*;
 
 use crate::lexer::{Token};
 
+use parsing::ruler::RepresentableToken;
+
 use helpers::{elvis, some_or};
 
 pub fn transform(
@@ -35,120 +37,10 @@ pub fn transform(
     return result;
 }
 
-pub fn suffix_to_base(suffix: &str) -> Option<u8> {
-    match suffix {
-        "b" => Some(2),
-        "o" => Some(8),
-        "h" => Some(16),
-        _ => None,
-    }
-}
-
-fn transform_numbers(
-    lefter: &Token,
-    righter: &Token,
-    target: &mut Vec<Token>
-) {
-    match (lefter, righter) {
-        (
-            Token::NumberSegment { value: lefter_value, base: lefter_base },
-            Token::Number { value: righter_value, base: righter_base }
-        ) => {
-            // it's only possible that the 2 tokens
-            // have different base
-
-            // special case: when the second number
-            // is 'b'_16 that must be treated as
-            // 'b' suffix of a binary number
-
-            target.pop();
-
-            if righter_value == "b" && *lefter_base == 2 {
-                target.push(lefter.clone());
-                return;
-            }
-
-            if *lefter_base > *righter_base {
-                target.push(
-                    Token::Text {
-                        value: lefter_value.clone() + &righter_value
-                    }
-                );
-                return;
-            }
-
-            target.push(
-                Token::Number {
-                    value: lefter_value.clone() + &righter_value,
-                    base: *righter_base
-                }
-            );
-        },
-        (
-            Token::NumberSegment { value: lefter_value, base },
-            Token::Text { value: righter_value }
-        ) => {
-            target.pop();
-
-            if let Some(desired_base) = suffix_to_base(righter_value) {
-                if *base <= desired_base {
-                    target.push(
-                        Token::Number {
-                            value: lefter_value.clone(),
-                            base: desired_base
-                        }
-                    );
-                    return;
-                }
-
-                target.push(
-                    Token::Text {
-                        value: lefter_value.clone() + &righter_value
-                    }
-                );
-                return;
-            }
-
-            target.push(
-                Token::Text {
-                    value: lefter_value.clone() + &righter_value
-                }
-            );
-        },
-        (
-            Token::Text { value: lefter_value },
-            Token::NumberSegment { value: righter_value, base: _ }
-        ) => {
-            target.pop();
-            target.push(
-                Token::Text {
-                    value: lefter_value.clone() + &righter_value
-                }
-            );
-        },
-        (
-            Token::NumberSegment { value: lefter_value, base },
-            _
-        ) => {
-            if *base <= 10 {
-                target.push(
-                    Token::Number {
-                        value: lefter_value.clone(),
-                        base: 10,
-                    }
-                );
-            } else {
-                target.push(
-                    Token::Text {
-                        value: lefter_value.clone()
-                    }
-                );
-            }
-        },
-        _ => {
-            target.push(lefter.clone());
-        },
-    }
+/// Brackets denote lists and subscripts,
+/// so they never clue to the strings.
+fn is_bracket(value: &str) -> bool {
+    value == "[" || value == "]"
 }
 
 fn transform_tight_tokens(
@@ -182,7 +74,7 @@ fn transform_tight_tokens(
         (
             Token::Operator { value: lefter_value },
             Token::Text { value: righter_value }
-        ) => {
+        ) if !is_bracket(lefter_value) => {
             target.pop();
             target.push(
                 Token::Text {
@@ -218,10 +110,279 @@ fn transform_tight_tokens(
     }
 }
 
+/// Operators that may come along with a `*`
+/// at the edge of a glob, as in `src/*`.
+const GLOB_OPERATORS: [&'static str; 3] = ["*", "/", "."];
+
+/// Whether a glob can clue to the last token.
+/// In `$list[0]` the brackets are a subscript,
+/// not a class.
+fn ends_with_word(result: &[Token]) -> bool {
+    match result {
+        [.., Token::Delimiter { value }, Token::Text { .. }] if value == "$" => false,
+        [.., Token::Text { .. }] => true,
+        _ => false,
+    }
+}
+
+fn starts_with_word(tokens: &[Token], index: usize) -> bool {
+    match tokens.get(index) {
+        Some(Token::Text { .. }) => true,
+        Some(Token::Operator { value }) => value == "*",
+        _ => false,
+    }
+}
+
+/// Reads the tokens up to the closing symbol
+/// into a single string, as long as none of
+/// them is a whitespace or a delimiter.
+fn read_group(
+    tokens: &[Token],
+    start: usize,
+    closing: &Token,
+    forbidden: &[&str],
+) -> Option<(String, usize)> {
+    let mut value = tokens[start].get_value()?.to_owned();
+    let mut index = start + 1;
+
+    loop {
+        let next = tokens.get(index)?;
+        index += 1;
+
+        if next == closing {
+            value += next.get_value()?;
+            return Some((value, index));
+        }
+
+        match next {
+            Token::Operator { value: it } if forbidden.contains(&it.as_str()) => return None,
+            Token::Operator { value: it } |
+            Token::Text { value: it } |
+            Token::Number { value: it, .. } => value += it,
+            _ => return None,
+        }
+    }
+}
+
+/// `[a-z]` next to a word is a class.
+fn read_class(tokens: &[Token], start: usize, result: &[Token]) -> Option<(String, usize)> {
+    let closing = Token::Operator { value: "]".to_owned() };
+    let (value, next) = read_group(tokens, start, &closing, &["[", ",", ":"])?;
+
+    if value == "[]" || !ends_with_word(result) && !starts_with_word(tokens, next) {
+        return None;
+    }
+
+    Some((value, next))
+}
+
+/// `{a,b}` is an alternation rather than
+/// a closure, since it has a comma and
+/// no whitespace.
+fn read_alternation(tokens: &[Token], start: usize) -> Option<(String, usize)> {
+    let closing = Token::Delimiter { value: "}".to_owned() };
+    let (value, next) = read_group(tokens, start, &closing, &[])?;
+
+    if !value.contains(',') || value.contains("->") {
+        return None;
+    }
+
+    Some((value, next))
+}
+
+/// A lone `*` is a multiplication, so `*`
+/// only becomes a part of a glob when it's
+/// glued to a word or to other operators.
+fn read_stars(tokens: &[Token], start: usize, result: &[Token]) -> Option<(String, usize)> {
+    let mut value = String::new();
+    let mut index = start;
+
+    while let Some(Token::Operator { value: it }) = tokens.get(index) {
+        if !GLOB_OPERATORS.contains(&it.as_str()) {
+            break;
+        }
+
+        value += it;
+        index += 1;
+    }
+
+    if !value.contains('*') {
+        return None;
+    }
+
+    let glued = index - start > 1 ||
+        ends_with_word(result) ||
+        starts_with_word(tokens, index) ||
+        tokens.get(index) == Some(&Token::Operator { value: "[".to_owned() }) ||
+        tokens.get(index) == Some(&Token::Delimiter { value: "{".to_owned() });
+
+    if !glued {
+        return None;
+    }
+
+    Some((value, index))
+}
+
+/// Turns the parts of globs that are lexed
+/// as operators and delimiters into texts,
+/// so that they clue to the rest of the word.
+fn transform_globs(tokens: &[Token]) -> Vec<Token> {
+    let mut result = vec![];
+    let mut index = 0;
+
+    while index < tokens.len() {
+        let glob = match &tokens[index] {
+            Token::Operator { value } if value == "[" => read_class(tokens, index, &result),
+            Token::Delimiter { value } if value == "{" => read_alternation(tokens, index),
+            Token::Operator { .. } => read_stars(tokens, index, &result),
+            _ => None,
+        };
+
+        match glob {
+            Some((value, next)) => {
+                result.push(Token::Text { value: value });
+                index = next;
+            },
+            None => {
+                result.push(tokens[index].clone());
+                index += 1;
+            },
+        }
+    }
+
+    result
+}
+
+/// Operators that are words when they
+/// are the last argument of a command,
+/// like in `cd -` or `cd /`.
+const TRAILING_WORDS: [&'static str; 2] = ["-", "/"];
+
+/// An operator at the end of a command
+/// can't be a binary one, so it may be
+/// taken as a word.
+fn transform_trailing_operators(tokens: &[Token]) -> Vec<Token> {
+    let mut result = tokens.to_vec();
+
+    for index in 1..tokens.len() {
+        let is_word = match &tokens[index] {
+            Token::Operator { value } => TRAILING_WORDS.contains(&value.as_str()),
+            _ => false,
+        };
+
+        let is_spaced = matches!(tokens[index - 1], Token::Whitespace { .. });
+
+        let next = tokens[index + 1..].iter()
+            .find(|it| !matches!(it, Token::Whitespace { .. }));
+
+        let is_last = match next {
+            Some(Token::Delimiter { value }) => value == ")" || value == "}",
+            Some(Token::Newline { .. }) | Some(Token::CommandEnd) | Some(Token::End) | None => true,
+            _ => false,
+        };
+
+        if is_word && is_spaced && is_last {
+            result[index] = Token::Text { value: tokens[index].get_value().unwrap_or("").to_owned() };
+        }
+    }
+
+    result
+}
+
+/// `%2` at the start of an argument refers
+/// to a job, as in `fg %2`, rather than
+/// being the remainder operator.
+fn transform_job_references(tokens: &[Token]) -> Vec<Token> {
+    let mut result = vec![];
+    let mut index = 0;
+
+    while index < tokens.len() {
+        let is_percent = matches!(&tokens[index], Token::Operator { value } if value == "%");
+        let is_spaced = index > 0 && matches!(tokens[index - 1], Token::Whitespace { .. });
+
+        if let (true, true, Some(Token::Number { value, .. })) = (is_percent, is_spaced, tokens.get(index + 1)) {
+            result.push(Token::Text { value: "%".to_owned() + value });
+            index += 2;
+            continue;
+        }
+
+        result.push(tokens[index].clone());
+        index += 1;
+    }
+
+    result
+}
+
+/// Words that are binary operators. They
+/// are only taken as operators inside of
+/// an expression, so `echo rock and roll`
+/// still gets three arguments.
+const WORD_OPERATORS: [&'static str; 6] = ["and", "or", "xor", "nand", "xnor", "in"];
+
+/// Whether the `in` at the index is the
+/// one of `for it in ...`.
+fn is_for_in(tokens: &[Token], index: usize) -> bool {
+    let mut previous = tokens[..index].iter()
+        .rev()
+        .filter(|it| !matches!(it, Token::Whitespace { .. }));
+
+    let variable = previous.next();
+    let keyword = previous.next();
+
+    matches!(variable, Some(Token::Text { .. }))
+        && matches!(keyword, Some(Token::Text { value }) if value == "for")
+}
+
+/// Turns the word operators into real ones
+/// within `( ... )` and in the `for` loop,
+/// following the nesting the same way the
+/// lexer does.
+fn transform_word_operators(tokens: &[Token]) -> Vec<Token> {
+    let mut result = tokens.to_vec();
+    let mut nesting_stack = vec![];
+
+    for index in 0..tokens.len() {
+        let context = nesting_stack.last().cloned();
+
+        match &tokens[index] {
+            Token::Delimiter { value } => {
+                match (context, value.as_str()) {
+                    (Some('\''), "'") | (Some('"'), "\"") => { nesting_stack.pop(); },
+                    (Some('\''), _) => {},
+                    (Some('"'), "(") => nesting_stack.push('('),
+                    (Some('"'), _) => {},
+                    (_, "\"") => nesting_stack.push('"'),
+                    (_, "'") => nesting_stack.push('\''),
+                    (_, "(") => nesting_stack.push('('),
+                    (_, "{") => nesting_stack.push('{'),
+                    (_, ")") | (_, "}") => { nesting_stack.pop(); },
+                    _ => {},
+                }
+            }
+            Token::Text { value } if WORD_OPERATORS.contains(&value.as_str()) => {
+                let is_expression = context == Some('(');
+                let is_loop = value == "in" && context != Some('"') && context != Some('\'') && is_for_in(tokens, index);
+
+                if is_expression || is_loop {
+                    result[index] = Token::Operator { value: value.clone() };
+                }
+            }
+            _ => {}
+        }
+    }
+
+    result
+}
+
 pub struct Liner<'a> {
     pub backend: &'a mut (dyn Stream<Token> + 'a),
     pub end_token_met: bool,
+    /// The source line the last
+    /// statement starts at.
     pub line_number: usize,
+    /// The source line the next
+    /// statement starts at.
+    pub next_line_number: usize,
 }
 
 impl <'a> Liner<'a> {
@@ -232,6 +393,12 @@ impl <'a> Liner<'a> {
             let next = self.backend.grab();
             line.push(next.clone());
 
+            match &next {
+                Token::Newline { .. } | Token::CommandEnd => self.next_line_number += 1,
+                Token::Whitespace { value } => self.next_line_number += value.matches('\n').count(),
+                _ => {},
+            }
+
             match next {
                 Token::CommandEnd => break,
                 Token::End => {
@@ -248,8 +415,12 @@ impl <'a> Liner<'a> {
         // }
         // println!("");
 
-        line = transform(&line, &transform_numbers);
         line = transform(&line, &transform_tight_tokens);
+        line = transform_globs(&line);
+        line = transform_trailing_operators(&line);
+        line = transform_job_references(&line);
+        line = transform(&line, &transform_tight_tokens);
+        line = transform_word_operators(&line);
 
         // line = line.iter()
         //     // .filter(|&it| match it {
@@ -269,6 +440,7 @@ impl <'a> Liner<'a> {
             backend: backend,
             end_token_met: false,
             line_number: 0,
+            next_line_number: 1,
         };
     }
 }
@@ -279,7 +451,7 @@ impl <'a> Stream<Vec<Token>> for Liner<'a> {
     }
 
     fn grab(&mut self) -> Vec<Token> {
-        self.line_number += 1;
+        self.line_number = self.next_line_number;
         return self.read_line();
     }
 
@@ -6,7 +6,7 @@ use crate::liner::{Liner};
 use crate::ast::*;
 use crate::ast::nodes::*;
 
-use parsing::ruler::{Grammar, apply_rule};
+use parsing::ruler::{Grammar, RepresentableToken, apply_rule};
 
 use crate::grammar::{get_grammar};
 
@@ -18,25 +18,39 @@ pub struct Parser<'a> {
     pub backend: Liner<'a>,
     pub last_ast: Rc<RefCell<Box<dyn Node>>>,
     pub end_token_met: bool,
+    /// What's wrong with the last statement,
+    /// it's skipped if it's malformed.
+    pub syntax_error: Option<String>,
+}
+
+fn describe_token(token: &Token) -> String {
+    match token.get_value() {
+        Some(value) => format!("{:?}", value),
+        None => token.get_type_name(),
+    }
+}
+
+fn create_empty() -> Rc<RefCell<Box<dyn Node>>> {
+    Rc::new(
+        RefCell::new(
+            Box::new(
+                Expressions {
+                    values: vec![]
+                }
+            )
+        )
+    )
 }
 
 impl <'a> Parser<'a> {
     fn parse(&mut self) -> Rc<RefCell<Box<dyn Node>>> {
+        self.syntax_error = None;
         let tokens = self.backend.grab();
 
         match tokens.first() {
             Some(Token::End) => {
                 self.end_token_met = true;
-
-                return Rc::new(
-                    RefCell::new(
-                        Box::new(
-                            Expressions {
-                                values: vec![]
-                            }
-                        )
-                    )
-                );
+                return create_empty();
             },
             _ => {},
         }
@@ -50,24 +64,18 @@ impl <'a> Parser<'a> {
 
         if stop_index > 0 {
             if stop_index < tokens.len() - 1 {
-                println!("Warning > Ignoring due to a syntax error at:");
-                println!("    {:?}", tokens[stop_index]);
-                println!("Which is right after:");
-                println!("    {:?}", tokens[stop_index - 1]);
+                self.syntax_error = Some(format!(
+                    "Unexpected {} > Right after {}",
+                    describe_token(&tokens[stop_index]),
+                    describe_token(&tokens[stop_index - 1]),
+                ));
+                return create_empty();
             }
         } else if tokens.len() >= 2 {
-            println!("Warning > Ignoring due to a syntax error at:");
-            println!("    {:?}", tokens[stop_index]);
+            self.syntax_error = Some(format!("Unexpected {}", describe_token(&tokens[stop_index])));
+            return create_empty();
         } else {
-            return Rc::new(
-                RefCell::new(
-                    Box::new(
-                        Expressions {
-                            values: vec![]
-                        }
-                    )
-                )
-            );
+            return create_empty();
         }
 
         if let Some(thing) = ast {
@@ -95,16 +103,9 @@ impl <'a> Parser<'a> {
         return Parser::<'a> {
             grammar: get_grammar(),
             backend: Liner::<'a>::new(backend),
-            last_ast: Rc::new(
-                RefCell::new(
-                    Box::new(
-                        Expressions {
-                            values: vec![]
-                        }
-                    )
-                )
-            ),
-            end_token_met: false
+            last_ast: create_empty(),
+            end_token_met: false,
+            syntax_error: None,
         };
     }
 }
@@ -4,7 +4,7 @@ use crate::stream::text_stream::*;
 
 /// Stream with an accumulator that saves
 /// every single character it has seen.
-pub trait AccumulatorStream : PeekableStream<Option<char>> {
+pub trait AccumulatorStream : BufferedStream<Option<char>> {
     /// Clears the interal lexeme.
     fn clear(&mut self);
 
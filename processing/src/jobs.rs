@@ -0,0 +1,410 @@
+use std::process::{Command, ExitStatus};
+use std::sync::atomic::{AtomicBool, Ordering};
+
+#[cfg(unix)]
+use std::os::unix::process::{CommandExt, ExitStatusExt};
+
+use crate::Redirections;
+use crate::signals::reset_signal;
+
+#[cfg(unix)]
+use crate::{launch_pipeline, resolve_outputs};
+#[cfg(unix)]
+use crate::signals::ignore_signal;
+
+/// The signals a shell with job control
+/// ignores, so that they only reach the
+/// jobs in the foreground.
+#[cfg(unix)]
+const JOB_CONTROL_SIGNALS: [i32; 4] = [
+    libc::SIGQUIT,
+    libc::SIGTSTP,
+    libc::SIGTTIN,
+    libc::SIGTTOU,
+];
+
+/// What a job is doing right now.
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub enum JobState {
+    Running,
+    /// Suspended via Ctrl-Z or `SIGSTOP`,
+    /// holds the signal.
+    Stopped(i32),
+    /// All the commands have finished, the
+    /// status is the one of the last command.
+    Done(ExitStatus),
+}
+
+impl JobState {
+    /// `Running`, `Stopped`, `Done`, `Exit 2`
+    /// or `Signal 9`.
+    pub fn describe(&self) -> String {
+        match self {
+            JobState::Running => "Running".to_owned(),
+            JobState::Stopped(..) => "Stopped".to_owned(),
+            JobState::Done(status) => {
+                if let Some(signal) = get_signal(status) {
+                    format!("Signal {}", signal)
+                } else if status.success() {
+                    "Done".to_owned()
+                } else {
+                    format!("Exit {}", status.code().unwrap_or(1))
+                }
+            }
+        }
+    }
+}
+
+#[cfg(unix)]
+fn get_signal(status: &ExitStatus) -> Option<i32> {
+    status.signal()
+}
+
+#[cfg(not(unix))]
+fn get_signal(_status: &ExitStatus) -> Option<i32> {
+    None
+}
+
+/// A pipeline whose commands share
+/// a process group, so that they can
+/// be stopped and resumed together.
+pub struct Job {
+    pub id: usize,
+    /// The pipeline as it's been written.
+    pub command: String,
+    /// The process group id, the same as
+    /// the pid of the first command.
+    pub group: i32,
+    pub state: JobState,
+    /// The pids of the commands and the
+    /// statuses of the ones that have finished.
+    processes: Vec<(i32, Option<ExitStatus>)>,
+}
+
+impl Job {
+    /// Collects whatever has happened to
+    /// the commands without blocking.
+    pub fn update(&mut self) {
+        let mut stopped = None;
+        let mut continued = false;
+
+        for (pid, status) in &mut self.processes {
+            if status.is_some() {
+                continue;
+            }
+
+            match wait_process(*pid, true) {
+                Change::Stopped(signal) => stopped = Some(signal),
+                Change::Continued => continued = true,
+                Change::Finished(it) => *status = Some(it),
+                Change::Nothing => {}
+            }
+        }
+
+        if let Some(status) = self.get_status() {
+            self.state = JobState::Done(status);
+        } else if let Some(signal) = stopped {
+            self.state = JobState::Stopped(signal);
+        } else if continued {
+            self.state = JobState::Running;
+        }
+    }
+
+    /// Blocks until all the commands
+    /// finish or the job gets stopped.
+    pub fn wait(&mut self) {
+        for (pid, status) in &mut self.processes {
+            if status.is_some() {
+                continue;
+            }
+
+            match wait_process(*pid, false) {
+                Change::Stopped(signal) => {
+                    self.state = JobState::Stopped(signal);
+                    return;
+                }
+                Change::Finished(it) => *status = Some(it),
+                _ => {}
+            }
+        }
+
+        if let Some(status) = self.get_status() {
+            self.state = JobState::Done(status);
+        }
+    }
+
+    /// Sends the signal to all the commands.
+    #[cfg(unix)]
+    pub fn signal(&self, signal: i32) -> std::io::Result<()> {
+        if unsafe { libc::killpg(self.group, signal) } == -1 {
+            return Err(std::io::Error::last_os_error());
+        }
+
+        Ok(())
+    }
+
+    #[cfg(not(unix))]
+    pub fn signal(&self, _signal: i32) -> std::io::Result<()> {
+        Err(unsupported())
+    }
+
+    /// Continues the stopped commands.
+    #[cfg(unix)]
+    pub fn resume(&mut self) -> std::io::Result<()> {
+        self.signal(libc::SIGCONT)?;
+
+        if let JobState::Stopped(..) = self.state {
+            self.state = JobState::Running;
+        }
+
+        Ok(())
+    }
+
+    #[cfg(not(unix))]
+    pub fn resume(&mut self) -> std::io::Result<()> {
+        Err(unsupported())
+    }
+
+    /// The status of the last command
+    /// once all of them have finished.
+    fn get_status(&self) -> Option<ExitStatus> {
+        let mut result = None;
+
+        for (_, status) in &self.processes {
+            result = Some((*status)?);
+        }
+
+        result
+    }
+}
+
+enum Change {
+    Nothing,
+    Stopped(i32),
+    Continued,
+    Finished(ExitStatus),
+}
+
+/// Collects what has happened to the process,
+/// `nonblocking` means returning right away if
+/// nothing has.
+#[cfg(unix)]
+fn wait_process(pid: i32, nonblocking: bool) -> Change {
+    let options = if nonblocking {
+        libc::WNOHANG | libc::WUNTRACED | libc::WCONTINUED
+    } else {
+        libc::WUNTRACED
+    };
+
+    let mut status = 0;
+    let mut result = unsafe { libc::waitpid(pid, &mut status, options) };
+
+    while result == -1 && std::io::Error::last_os_error().kind() == std::io::ErrorKind::Interrupted {
+        result = unsafe { libc::waitpid(pid, &mut status, options) };
+    }
+
+    // someone else has already collected it,
+    // there's nothing to wait for anymore
+    if result == -1 {
+        return Change::Finished(ExitStatus::from_raw(0));
+    }
+
+    if result == 0 {
+        return Change::Nothing;
+    }
+
+    let status = ExitStatus::from_raw(status);
+
+    if let Some(signal) = status.stopped_signal() {
+        Change::Stopped(signal)
+    } else if status.continued() {
+        Change::Continued
+    } else {
+        Change::Finished(status)
+    }
+}
+
+#[cfg(not(unix))]
+fn wait_process(_pid: i32, _nonblocking: bool) -> Change {
+    Change::Finished(ExitStatus::default())
+}
+
+#[cfg(not(unix))]
+fn unsupported() -> std::io::Error {
+    std::io::Error::new(std::io::ErrorKind::Unsupported, "Job control needs a Unix system")
+}
+
+/// Set by `enable_job_control()`.
+static JOB_CONTROL: AtomicBool = AtomicBool::new(false);
+
+/// Puts the command into the process group
+/// of the `leader`, or into a new one if it's
+/// 0, and undoes what `enable_job_control()`
+/// has done to the signals. With `foreground`
+/// set, the group also gets the terminal.
+/// Both happen in the child itself, so that
+/// it never runs before they're done.
+#[cfg(unix)]
+pub(crate) fn prepare_member(command: &mut Command, leader: Option<i32>, foreground: bool) {
+    let job_control = JOB_CONTROL.load(Ordering::SeqCst);
+
+    if leader.is_none() && !job_control {
+        return;
+    }
+
+    unsafe {
+        command.pre_exec(move || {
+            if let Some(it) = leader {
+                if libc::setpgid(0, it) == -1 {
+                    return Err(std::io::Error::last_os_error());
+                }
+            }
+
+            if !job_control {
+                return Ok(());
+            }
+
+            // `SIGTTOU` is still ignored here,
+            // so this can't stop the child
+            if leader.is_some() && foreground && is_terminal() {
+                libc::tcsetpgrp(libc::STDIN_FILENO, libc::getpgrp());
+            }
+
+            for it in &JOB_CONTROL_SIGNALS {
+                libc::signal(*it, libc::SIG_DFL);
+            }
+
+            Ok(())
+        });
+    }
+}
+
+#[cfg(not(unix))]
+pub(crate) fn prepare_member(_command: &mut Command, _leader: Option<i32>, _foreground: bool) {}
+
+/// Launches the pipeline in a new process
+/// group without waiting for it.
+#[cfg(unix)]
+pub fn start_job(
+    id: usize,
+    command: &str,
+    redirections: &Redirections,
+    environment: &[(String, String)],
+    arguments: &[Vec<String>],
+    own: &[Redirections],
+    foreground: bool
+) -> std::io::Result<Job> {
+    let output = redirections.output.try_clone()?;
+    let (output, error) = resolve_outputs(output, &redirections.error)?;
+    let launched = launch_pipeline(&redirections.input, &output, &error, environment, arguments, own, true, foreground);
+
+    // the commands that have been spawned may
+    // have taken the terminal already
+    if launched.is_err() && foreground && JOB_CONTROL.load(Ordering::SeqCst) {
+        take_terminal();
+    }
+
+    let children = launched?;
+
+    // the statuses are collected via `waitpid()`,
+    // so the children aren't needed anymore
+    let processes = children.iter()
+        .map(|it| (it.id() as i32, None))
+        .collect::<Vec<(i32, Option<ExitStatus>)>>();
+
+    Ok(Job {
+        id: id,
+        command: command.to_owned(),
+        group: processes[0].0,
+        state: JobState::Running,
+        processes: processes,
+    })
+}
+
+#[cfg(not(unix))]
+pub fn start_job(
+    _id: usize,
+    _command: &str,
+    _redirections: &Redirections,
+    _environment: &[(String, String)],
+    _arguments: &[Vec<String>],
+    _own: &[Redirections],
+    _foreground: bool
+) -> std::io::Result<Job> {
+    Err(unsupported())
+}
+
+#[cfg(unix)]
+fn is_terminal() -> bool {
+    unsafe { libc::isatty(libc::STDIN_FILENO) == 1 }
+}
+
+/// Puts the shell into its own process group,
+/// makes it the foreground one and ignores
+/// the signals the terminal sends, so that
+/// Ctrl-Z and Ctrl-\ only affect the jobs.
+/// Returns false if there's no terminal
+/// to control.
+#[cfg(unix)]
+pub fn enable_job_control() -> std::io::Result<bool> {
+    if !is_terminal() {
+        return Ok(false);
+    }
+
+    for it in &JOB_CONTROL_SIGNALS {
+        ignore_signal(*it)?;
+    }
+
+    JOB_CONTROL.store(true, Ordering::SeqCst);
+
+    unsafe {
+        let pid = libc::getpid();
+
+        if libc::getpgrp() != pid && libc::setpgid(pid, pid) == -1 {
+            return Err(std::io::Error::last_os_error());
+        }
+    }
+
+    take_terminal();
+    Ok(true)
+}
+
+#[cfg(not(unix))]
+pub fn enable_job_control() -> std::io::Result<bool> {
+    Ok(false)
+}
+
+/// Brings back what the shell does
+/// with the signal by default.
+pub fn restore_signal(signal: i32) -> std::io::Result<()> {
+    #[cfg(unix)]
+    if JOB_CONTROL.load(Ordering::SeqCst) && JOB_CONTROL_SIGNALS.contains(&signal) {
+        return ignore_signal(signal);
+    }
+
+    reset_signal(signal)
+}
+
+/// Lets the group read from the terminal
+/// and receive the signals it sends.
+#[cfg(unix)]
+pub fn give_terminal(group: i32) {
+    if is_terminal() {
+        unsafe { libc::tcsetpgrp(libc::STDIN_FILENO, group) };
+    }
+}
+
+#[cfg(not(unix))]
+pub fn give_terminal(_group: i32) {}
+
+/// Makes the shell the foreground
+/// process group again.
+#[cfg(unix)]
+pub fn take_terminal() {
+    if is_terminal() {
+        unsafe { libc::tcsetpgrp(libc::STDIN_FILENO, libc::getpgrp()) };
+    }
+}
+
+#[cfg(not(unix))]
+pub fn take_terminal() {}
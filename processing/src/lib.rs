@@ -1,68 +1,312 @@
-use std::process::{Command, Child, Stdio};
+use std::process::{Command, Child, Stdio, ExitStatus};
+
+use std::io::{Read, Write, PipeReader, PipeWriter};
+use std::fs::File;
+
+use helpers::{elvis, some_or};
 
 use tempfile::NamedTempFile;
 
+pub mod jobs;
+pub mod signals;
+pub mod users;
+
 pub fn launch_command(arguments: &[&str]) -> std::io::Result<Child> {
     Command::new(arguments[0])
         .args(&arguments[1..])
         .spawn()
 }
 
-pub fn launch_pipeline<T: Into<Stdio>, K: Into<Stdio>>(
-    input: Option<T>,
-    output: Option<K>,
-    arguments: &Vec<Vec<String>>
-) -> std::io::Result<Child> {
-    if arguments.len() == 1 {
-        let mut command = Command::new(&arguments[0][0]);
-        command.args(&arguments[0][1..]);
-
-        if let Some(pipe) = input {
-            command.stdin(pipe);
-        }
+/// Defines what happens to the output
+/// of the last command of a pipeline.
+pub enum OutputMode {
+    /// The output goes straight to the
+    /// shell's stdout as soon as it's written.
+    Inherit,
+    /// The output is collected into memory
+    /// and returned once the pipeline finishes.
+    Capture,
+}
+
+/// What's left after a pipeline has finished.
+pub struct PipelineResult {
+    /// The status of the last command.
+    pub status: ExitStatus,
+    /// The collected output, empty unless
+    /// `OutputMode::Capture` was used.
+    pub output: Vec<u8>,
+}
+
+/// Where a standard stream of
+/// a pipeline is connected to.
+pub enum Channel {
+    /// The same stream the shell uses.
+    Inherit,
+    /// The null device, reading gives
+    /// nothing and writing is discarded.
+    Null,
+    /// A file opened by the shell.
+    File(File),
+    /// The write end of a pipe.
+    Pipe(PipeWriter),
+    /// The place the pipeline stdout goes
+    /// to. Only makes sense for the stderr.
+    Output,
+    /// The place the pipeline stderr goes
+    /// to. Only makes sense for the stdout.
+    Error,
+}
+
+impl Channel {
+    pub fn try_clone(&self) -> std::io::Result<Channel> {
+        Ok(match self {
+            Channel::Inherit => Channel::Inherit,
+            Channel::Null => Channel::Null,
+            Channel::File(file) => Channel::File(file.try_clone()?),
+            Channel::Pipe(pipe) => Channel::Pipe(pipe.try_clone()?),
+            Channel::Output => Channel::Output,
+            Channel::Error => Channel::Error,
+        })
+    }
+
+    /// Creates a new handle for a child. `Output`
+    /// and `Error` that are left after `resolve_outputs()`
+    /// mean the shell's own stdout and stderr.
+    pub fn to_stdio(&self) -> std::io::Result<Stdio> {
+        Ok(match self {
+            Channel::Inherit => Stdio::inherit(),
+            Channel::Null => Stdio::null(),
+            Channel::File(file) => Stdio::from(file.try_clone()?),
+            Channel::Pipe(pipe) => Stdio::from(pipe.try_clone()?),
+            Channel::Output => Stdio::from(std::io::stdout()),
+            Channel::Error => Stdio::from(std::io::stderr()),
+        })
+    }
+
+    pub fn is_inherit(&self) -> bool {
+        matches!(self, Channel::Inherit)
+    }
 
-        if let Some(pipe) = output {
-            command.stdout(pipe);
+    /// Writes on behalf of the shell itself.
+    /// `Inherit` and the references to the
+    /// other stream write to the `fallback`.
+    pub fn write_all(&self, data: &[u8], fallback: &mut dyn Write) -> std::io::Result<()> {
+        match self {
+            Channel::File(file) => (&*file).write_all(data),
+            Channel::Pipe(pipe) => (&*pipe).write_all(data),
+            Channel::Null => Ok(()),
+            _ => fallback.write_all(data),
         }
+    }
+}
+
+/// The standard streams of a pipeline.
+pub struct Redirections {
+    pub input: Channel,
+    pub output: Channel,
+    pub error: Channel,
+}
 
-        return command.spawn();
+impl Redirections {
+    pub fn new() -> Redirections {
+        Redirections {
+            input: Channel::Inherit,
+            output: Channel::Inherit,
+            error: Channel::Inherit,
+        }
     }
+}
 
-    let mut first_command = Command::new(&arguments[0][0]);
-    first_command.args(&arguments[0][1..]);
-    first_command.stdout(Stdio::piped());
+/// Replaces `Output` and `Error` with the
+/// channels they refer to. If they refer to
+/// each other, both streams stay as they are.
+fn resolve_outputs(output: Channel, error: &Channel) -> std::io::Result<(Channel, Channel)> {
+    match (&output, error) {
+        (Channel::Error, Channel::Output) => {
+            Ok((Channel::Inherit, Channel::Inherit))
+        }
+        (Channel::Error, it) if !it.is_inherit() => {
+            Ok((it.try_clone()?, it.try_clone()?))
+        }
+        (it, Channel::Output) if !it.is_inherit() => {
+            let error = it.try_clone()?;
+            Ok((output, error))
+        }
+        _ => {
+            Ok((output, error.try_clone()?))
+        }
+    }
+}
 
-    if let Some(pipe) = input {
-        first_command.stdin(pipe);
+/// Connects the standard streams of a command
+/// of a pipeline. Its `own` redirections take
+/// precedence over the pipes and the streams
+/// of the whole pipeline. Returns the read end
+/// of the pipe the next command reads from.
+fn connect_streams(
+    command: &mut Command,
+    own: &Redirections,
+    input: Option<Stdio>,
+    output: &Channel,
+    error: &Channel,
+    is_last: bool
+) -> std::io::Result<Option<PipeReader>> {
+    if !own.input.is_inherit() {
+        command.stdin(own.input.to_stdio()?);
+    } else if let Some(pipe) = input {
+        command.stdin(pipe);
     }
 
-    let mut last_child = first_command.spawn()?;
+    let mut reader = None;
+
+    let default_output = if is_last {
+        output.try_clone()?
+    } else {
+        let (pipe_reader, pipe_writer) = std::io::pipe()?;
+        reader = Some(pipe_reader);
+        Channel::Pipe(pipe_writer)
+    };
 
-    for it in 1..arguments.len() - 1 {
-        if let Some(last_child_output) = last_child.stdout {
-            last_child = Command::new(&arguments[it][0])
-                .args(&arguments[it][1..])
-                .stdin(last_child_output)
-                .stdout(Stdio::piped())
-                .spawn()?;
+    let (command_output, command_error) = if own.output.is_inherit() && own.error.is_inherit() {
+        (default_output, error.try_clone()?)
+    } else {
+        let command_output = if own.output.is_inherit() { default_output } else { own.output.try_clone()? };
+        let command_error = if own.error.is_inherit() { error } else { &own.error };
+        resolve_outputs(command_output, command_error)?
+    };
+
+    command.stdout(command_output.to_stdio()?);
+    command.stderr(command_error.to_stdio()?);
+
+    Ok(reader)
+}
+
+/// Spawns the commands connecting each one's
+/// stdout to the next one's stdin. The `input`
+/// goes to the first command, the `output` is
+/// used by the last one, and all of them write
+/// their stderr to `error`, unless their `own`
+/// redirections say otherwise. The `environment`
+/// replaces the one of the shell process.
+/// With `group` set, the commands are put
+/// into a new process group, the one of the
+/// first command, and with `foreground` also
+/// set, that group gets the terminal.
+/// Returns all the children in the same order.
+pub fn launch_pipeline(
+    input: &Channel,
+    output: &Channel,
+    error: &Channel,
+    environment: &[(String, String)],
+    arguments: &[Vec<String>],
+    own: &[Redirections],
+    group: bool,
+    foreground: bool
+) -> std::io::Result<Vec<Child>> {
+    let mut children: Vec<Child> = vec![];
+    let mut next_input: Option<Stdio> = Some(input.to_stdio()?);
+
+    for (index, it) in arguments.iter().enumerate() {
+        let is_last = index == arguments.len() - 1;
+
+        let mut command = Command::new(&it[0]);
+        command.args(&it[1..]);
+        command.env_clear();
+        command.envs(environment.iter().map(|(key, value)| (key, value)));
+
+        let leader = if group {
+            Some(children.first().map(|it| it.id() as i32).unwrap_or(0))
         } else {
-            return Err(std::io::Error::from(std::io::ErrorKind::BrokenPipe));
-        }
+            None
+        };
+
+        jobs::prepare_member(&mut command, leader, foreground);
+
+        let spawned = connect_streams(&mut command, &own[index], next_input.take(), output, error, is_last)
+            .and_then(|reader| Ok((command.spawn()?, reader)));
+
+        let (child, reader) = match spawned {
+            Ok(it) => it,
+            Err(error) => {
+                kill_all(children);
+                return Err(error);
+            }
+        };
+
+        next_input = reader.map(Stdio::from);
+        children.push(child);
+    }
+
+    return Ok(children);
+}
+
+/// Stops the children that have already been
+/// spawned, so that waiting for them doesn't
+/// hang, like `cat` in `cat | nonexistent`,
+/// and reaps them. The errors are ignored.
+fn kill_all(children: Vec<Child>) {
+    for mut it in children {
+        let _ = it.kill();
+        let _ = it.wait();
     }
+}
 
-    if let Some(last_child_output) = last_child.stdout {
-        let mut last_command = Command::new(&arguments[arguments.len() - 1][0]);
-        last_command.args(&arguments[arguments.len() - 1][1..]);
-        last_command.stdin(last_child_output);
+/// Launches the pipeline and waits for
+/// all of its commands to finish. The
+/// capturing only happens if the stdout
+/// hasn't been redirected elsewhere.
+pub fn run_pipeline(
+    mode: OutputMode,
+    redirections: &Redirections,
+    environment: &[(String, String)],
+    arguments: &[Vec<String>],
+    own: &[Redirections]
+) -> std::io::Result<PipelineResult> {
+    let mut reader = None;
 
-        if let Some(pipe) = output {
-            last_command.stdout(pipe);
+    let output = match mode {
+        OutputMode::Capture if redirections.output.is_inherit() => {
+            let (pipe_reader, pipe_writer) = std::io::pipe()?;
+            reader = Some(pipe_reader);
+            Channel::Pipe(pipe_writer)
         }
+        _ => redirections.output.try_clone()?,
+    };
 
-        return last_command.spawn();
-    } else {
-        return Err(std::io::Error::from(std::io::ErrorKind::BrokenPipe));
+    let (output, error) = resolve_outputs(output, &redirections.error)?;
+    let launched = launch_pipeline(&redirections.input, &output, &error, environment, arguments, own, false, false);
+
+    // our own copies of the write end must be
+    // closed, otherwise reading never ends
+    drop(output);
+    drop(error);
+
+    let mut children = launched?;
+    let mut collected = vec![];
+
+    // reading must happen before waiting,
+    // otherwise a child may block on a full pipe
+    if let Some(mut pipe_reader) = reader {
+        if let Err(error) = pipe_reader.read_to_end(&mut collected) {
+            kill_all(children);
+            return Err(error);
+        }
     }
+
+    let mut status = None;
+
+    for it in &mut children {
+        status = Some(it.wait()?);
+    }
+
+    let status = some_or! { status => {
+        return Err(std::io::Error::from(std::io::ErrorKind::InvalidInput));
+    }};
+
+    return Ok(PipelineResult {
+        status: status,
+        output: collected,
+    });
 }
 
 pub fn launch_input_substitution(arguments: &[&str]) -> std::io::Result<(NamedTempFile, Child)> {
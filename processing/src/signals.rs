@@ -0,0 +1,163 @@
+#[cfg(unix)]
+use std::sync::atomic::{AtomicBool, Ordering};
+
+pub use libc::SIGINT;
+
+/// Linux has 64 signals,
+/// numbered from 1.
+#[cfg(unix)]
+const SIGNAL_COUNT: usize = 65;
+
+/// The signals that have arrived
+/// but haven't been handled yet.
+#[cfg(unix)]
+static PENDING: [AtomicBool; SIGNAL_COUNT] = [const { AtomicBool::new(false) }; SIGNAL_COUNT];
+
+/// The names without the `SIG` prefix.
+#[cfg(unix)]
+const SIGNALS: [(&str, i32); 16] = [
+    ("HUP", libc::SIGHUP),
+    ("INT", libc::SIGINT),
+    ("QUIT", libc::SIGQUIT),
+    ("KILL", libc::SIGKILL),
+    ("USR1", libc::SIGUSR1),
+    ("USR2", libc::SIGUSR2),
+    ("PIPE", libc::SIGPIPE),
+    ("ALRM", libc::SIGALRM),
+    ("TERM", libc::SIGTERM),
+    ("CHLD", libc::SIGCHLD),
+    ("CONT", libc::SIGCONT),
+    ("STOP", libc::SIGSTOP),
+    ("TSTP", libc::SIGTSTP),
+    ("TTIN", libc::SIGTTIN),
+    ("TTOU", libc::SIGTTOU),
+    ("WINCH", libc::SIGWINCH),
+];
+
+#[cfg(not(unix))]
+const SIGNALS: [(&str, i32); 2] = [
+    ("INT", libc::SIGINT),
+    ("TERM", libc::SIGTERM),
+];
+
+/// `KILL`, `SIGKILL` or `9`.
+pub fn find_signal(name: &str) -> Option<i32> {
+    if let Ok(number) = name.parse::<i32>() {
+        return Some(number);
+    }
+
+    let name = name.strip_prefix("SIG").unwrap_or(name);
+
+    SIGNALS.iter()
+        .find(|(it, _)| *it == name)
+        .map(|(_, signal)| *signal)
+}
+
+/// `INT` for 2, or the number
+/// if the signal has no name.
+pub fn get_signal_name(signal: i32) -> String {
+    match SIGNALS.iter().find(|(_, it)| *it == signal) {
+        Some((name, _)) => name.to_string(),
+        None => signal.to_string(),
+    }
+}
+
+/// Sends the signal to a single process.
+#[cfg(unix)]
+pub fn send_signal(pid: i32, signal: i32) -> std::io::Result<()> {
+    if unsafe { libc::kill(pid, signal) } == -1 {
+        return Err(std::io::Error::last_os_error());
+    }
+
+    Ok(())
+}
+
+#[cfg(not(unix))]
+pub fn send_signal(_pid: i32, _signal: i32) -> std::io::Result<()> {
+    Err(unsupported())
+}
+
+/// Only the flag is set, the rest
+/// isn't safe inside of a handler.
+#[cfg(unix)]
+extern "C" fn remember(signal: libc::c_int) {
+    if let Some(it) = PENDING.get(signal as usize) {
+        it.store(true, Ordering::SeqCst);
+    }
+}
+
+#[cfg(unix)]
+fn set_handler(signal: i32, handler: libc::sighandler_t) -> std::io::Result<()> {
+    if signal <= 0 || signal as usize >= SIGNAL_COUNT {
+        return Err(std::io::Error::from(std::io::ErrorKind::InvalidInput));
+    }
+
+    if unsafe { libc::signal(signal, handler) } == libc::SIG_ERR {
+        return Err(std::io::Error::last_os_error());
+    }
+
+    Ok(())
+}
+
+/// Instead of its usual effect, the signal
+/// is kept until `take_signal()`.
+#[cfg(unix)]
+pub fn catch_signal(signal: i32) -> std::io::Result<()> {
+    set_handler(signal, remember as extern "C" fn(libc::c_int) as libc::sighandler_t)
+}
+
+#[cfg(not(unix))]
+pub fn catch_signal(_signal: i32) -> std::io::Result<()> {
+    Err(unsupported())
+}
+
+#[cfg(unix)]
+pub fn ignore_signal(signal: i32) -> std::io::Result<()> {
+    set_handler(signal, libc::SIG_IGN)
+}
+
+#[cfg(not(unix))]
+pub fn ignore_signal(_signal: i32) -> std::io::Result<()> {
+    Err(unsupported())
+}
+
+/// Brings back the usual effect.
+#[cfg(unix)]
+pub fn reset_signal(signal: i32) -> std::io::Result<()> {
+    set_handler(signal, libc::SIG_DFL)
+}
+
+#[cfg(not(unix))]
+pub fn reset_signal(_signal: i32) -> std::io::Result<()> {
+    Err(unsupported())
+}
+
+/// Acts as if the signal has been caught.
+#[cfg(unix)]
+pub fn add_signal(signal: i32) {
+    remember(signal);
+}
+
+#[cfg(not(unix))]
+pub fn add_signal(_signal: i32) {}
+
+/// Whether the signal has been caught since
+/// the last call. Other signals are left for
+/// the ones that expect them.
+#[cfg(unix)]
+pub fn take_signal(signal: i32) -> bool {
+    match PENDING.get(signal as usize) {
+        Some(it) => it.swap(false, Ordering::SeqCst),
+        None => false,
+    }
+}
+
+#[cfg(not(unix))]
+pub fn take_signal(_signal: i32) -> bool {
+    false
+}
+
+#[cfg(not(unix))]
+fn unsupported() -> std::io::Error {
+    std::io::Error::new(std::io::ErrorKind::Unsupported, "Signals need a Unix system")
+}
@@ -0,0 +1,50 @@
+#[cfg(unix)]
+use std::ffi::{CStr, CString};
+
+/// The buffer `getpwnam_r()` starts with
+/// if the system doesn't suggest one.
+#[cfg(unix)]
+const INITIAL_BUFFER_SIZE: usize = 1024;
+
+/// Keeps a broken entry from making
+/// the buffer grow forever.
+#[cfg(unix)]
+const MAX_BUFFER_SIZE: usize = 1 << 20;
+
+/// The home directory of the user as the
+/// system knows it, so the users that come
+/// from NSS, LDAP and the like are found too.
+#[cfg(unix)]
+pub fn find_home_directory(user: &str) -> Option<String> {
+    let name = CString::new(user).ok()?;
+
+    let suggested = unsafe { libc::sysconf(libc::_SC_GETPW_R_SIZE_MAX) };
+    let mut size = if suggested > 0 { suggested as usize } else { INITIAL_BUFFER_SIZE };
+
+    loop {
+        let mut buffer = vec![0 as libc::c_char; size];
+        let mut entry: libc::passwd = unsafe { std::mem::zeroed() };
+        let mut result: *mut libc::passwd = std::ptr::null_mut();
+
+        let code = unsafe {
+            libc::getpwnam_r(name.as_ptr(), &mut entry, buffer.as_mut_ptr(), size, &mut result)
+        };
+
+        if code == libc::ERANGE && size < MAX_BUFFER_SIZE {
+            size *= 2;
+            continue;
+        }
+
+        if code != 0 || result.is_null() || entry.pw_dir.is_null() {
+            return None;
+        }
+
+        let directory = unsafe { CStr::from_ptr(entry.pw_dir) };
+        return Some(directory.to_string_lossy().into_owned());
+    }
+}
+
+#[cfg(not(unix))]
+pub fn find_home_directory(_user: &str) -> Option<String> {
+    None
+}
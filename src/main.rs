@@ -1,10 +1,11 @@
 // use ferris_says::say;
 
 // use std::io::{stdout, BufWriter};
-use std::io::Write;
+use std::io::{Write, Cursor};
 
 use parsing::stream::{Stream};
 use parsing::stream::stdin_stream::{StdinStream};
+use parsing::stream::wrapper_stream::{WrapperStream};
 use parsing::stream::text_stream::{TextStream};
 use parsing::stream::accumulator_stream::{SimpleAccumulatorStream};
 
@@ -20,6 +21,10 @@ use terminals::terminal_stream::TerminalStream;
 // use std::fs::File;
 
 use backend::runner::Runner;
+use backend::interpreter::skip_shebang;
+
+use processing::jobs::enable_job_control;
+use processing::signals::{SIGINT, catch_signal, take_signal};
 
 use backend::cast;
 use backend::value;
@@ -33,6 +38,9 @@ enum InputMode {
     OffScreen {
         user_input: StdinStream
     },
+    Script {
+        user_input: WrapperStream<Cursor<String>>
+    },
 }
 
 impl InputMode {
@@ -40,33 +48,172 @@ impl InputMode {
         match self {
             InputMode::Interactive { user_input } => user_input,
             InputMode::OffScreen { user_input } => user_input,
+            InputMode::Script { user_input } => user_input,
         }
     }
-}
 
-fn main() {
-    let mut user_input = if is_interactive() {
-        println!("Welcome!");
-        InputMode::Interactive {
-            user_input: TerminalStream::new()
+    fn is_interactive(&self) -> bool {
+        match self {
+            InputMode::Interactive { .. } => true,
+            _ => false,
         }
+    }
+}
+
+const USAGE: &'static str = "Usage: cash [script [arguments...]]
+       cash -c <code> [arguments...]";
+
+/// What the shell has been asked
+/// to run via the command line.
+enum Invocation {
+    /// No script given - read commands
+    /// from the terminal or stdin.
+    Session,
+    /// Run the given file.
+    File {
+        path: String,
+        arguments: Vec<String>,
+    },
+    /// Run the code passed via `-c`.
+    Inline {
+        code: String,
+        arguments: Vec<String>,
+    },
+}
+
+fn parse_invocation(mut arguments: Vec<String>) -> Result<Invocation, String> {
+    let program = if arguments.is_empty() {
+        "cash".to_owned()
     } else {
-        InputMode::OffScreen {
-            user_input: StdinStream::new()
+        arguments.remove(0)
+    };
+
+    if arguments.is_empty() {
+        return Ok(Invocation::Session);
+    }
+
+    if arguments[0] == "-c" {
+        if arguments.len() < 2 {
+            return Err("Option -c requires an argument".to_owned());
+        }
+
+        let code = arguments.remove(1);
+        arguments[0] = program;
+
+        return Ok(Invocation::Inline {
+            code: code,
+            arguments: arguments,
+        });
+    }
+
+    if arguments[0] == "-h" || arguments[0] == "--help" {
+        return Err(String::new());
+    }
+
+    if arguments[0].starts_with("-") && arguments[0] != "--" {
+        return Err(format!("Unknown option {:?}", &arguments[0]));
+    }
+
+    if arguments[0] == "--" {
+        arguments.remove(0);
+
+        if arguments.is_empty() {
+            return Ok(Invocation::Session);
         }
+    }
+
+    return Ok(Invocation::File {
+        path: arguments[0].clone(),
+        arguments: arguments,
+    });
+}
+
+fn main() {
+    let invocation = match parse_invocation(std::env::args().collect()) {
+        Ok(it) => it,
+        Err(message) => {
+            if message.is_empty() {
+                println!("{}", USAGE);
+                std::process::exit(0);
+            }
+
+            eprintln!("cash: {}", message);
+            eprintln!("{}", USAGE);
+            std::process::exit(2);
+        }
+    };
+
+    let mut runner = Runner::new();
+
+    let mut user_input = match invocation {
+        Invocation::Session => {
+            if is_interactive() {
+                println!("Welcome!");
+                InputMode::Interactive {
+                    user_input: TerminalStream::new()
+                }
+            } else {
+                InputMode::OffScreen {
+                    user_input: StdinStream::new()
+                }
+            }
+        },
+        Invocation::File { path, arguments } => {
+            let code = match std::fs::read_to_string(&path) {
+                Ok(it) => it,
+                Err(error) => {
+                    eprintln!("cash: {}: {}", &path, error);
+                    std::process::exit(127);
+                }
+            };
+
+            runner.set_arguments(&arguments);
+
+            InputMode::Script {
+                user_input: WrapperStream::new(Cursor::new(skip_shebang(code)))
+            }
+        },
+        Invocation::Inline { code, arguments } => {
+            runner.set_arguments(&arguments);
+
+            InputMode::Script {
+                user_input: WrapperStream::new(Cursor::new(code))
+            }
+        },
     };
 
+    let interactive = user_input.is_interactive();
+
+    runner.interactive = interactive;
+
+    if interactive {
+        runner.job_control = match enable_job_control() {
+            Ok(it) => it,
+            Err(error) => {
+                eprintln!("Warning > Couldn't enable job control > {}", error);
+                false
+            }
+        };
+
+        // Ctrl-C interrupts the current
+        // statement rather than the shell,
+        // scripts only do it via `trap`
+        if let Err(error) = catch_signal(SIGINT) {
+            eprintln!("Warning > Couldn't catch Ctrl-C > {}", error);
+        }
+    }
+
     let mut accumulator_stream = SimpleAccumulatorStream::new(user_input.get_stream());
     let mut tokenizer = Lexer::new(&mut accumulator_stream);
     let mut parser = Parser::new(&mut tokenizer);
-    let mut runner = Runner::new();
 
     loop {
         if !parser.has_next() {
             break;
         }
 
-        if is_interactive() {
+        if interactive {
+            runner.report_jobs();
             print!("$ ");
             result_or! { std::io::stdout().flush() => break };
         }
@@ -74,17 +221,36 @@ fn main() {
         let wrapped = parser.grab();
         let mut ast = wrapped.borrow_mut();
 
+        runner.line = parser.get_offset();
+
+        // Ctrl-C at the prompt only
+        // discards the input
+        if interactive {
+            take_signal(SIGINT);
+        }
+
         // if is_interactive() {
         //     ast.accept_leveled_visitor(&mut ASTPrinter, 0);
         // }
 
-        ast.accept_simple_visitor(&mut runner);
+        match parser.syntax_error.take() {
+            Some(message) => {
+                runner.fail_syntax(&message);
 
-        if is_interactive() {
+                if let Some(error) = &runner.error {
+                    runner.print_error(&error.report());
+                }
+            }
+            None => runner.run(&mut ast),
+        }
+
+        if interactive {
             if let Some(string) = cast!(runner.value => value::string::StringValue) {
                 println!("::: {:?} :::", string);
             } else if let Some(number) = cast!(runner.value => value::number::NumberValue) {
                 println!("::: {:?} :::", number);
+            } else if let Some(float) = cast!(runner.value => value::float::FloatValue) {
+                println!("::: {:?} :::", float);
             } else if let Some(boolean) = cast!(runner.value => value::boolean::BooleanValue) {
                 println!("::: {:?} :::", boolean);
             } else if let Some(closure) = cast!(runner.value => value::closure::ClosureValue) {
@@ -101,5 +267,14 @@ fn main() {
         if runner.should_exit {
             break;
         }
+
+        // scripts stop at the first error
+        // nobody has handled
+        if !interactive && runner.error.is_some() {
+            break;
+        }
     }
+
+    runner.run_exit_trap();
+    std::process::exit(runner.exit_code);
 }